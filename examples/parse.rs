@@ -5,7 +5,7 @@ fn main() {
     let path = std::path::Path::new(&path);
     let (game, _) = slp_parser::read_game(path).unwrap();
 
-    //for i in game.high_port_frames.iter() {
+    //for i in game.port_frames(Port::High).iter() {
     //    println!("{:?}", i.stock_count);
     //}
     let t = std::time::Instant::now();
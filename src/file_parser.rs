@@ -5,7 +5,7 @@ const GAME_START:           u8 = 0x36;
 const PRE_FRAME_UPDATE:     u8 = 0x37;
 const POST_FRAME_UPDATE:    u8 = 0x38;
 const GAME_END:             u8 = 0x39;
-//const FRAME_START:          u8 = 0x3A;
+const FRAME_START:           u8 = 0x3A;
 const ITEM_UPDATE:          u8 = 0x3B;
 const FRAME_BOOKEND:        u8 = 0x3C;
 
@@ -13,7 +13,8 @@ const FOD_INFO:             u8 = 0x3F;
 const DREAMLAND_INFO:       u8 = 0x40;
 const STADIUM_INFO:         u8 = 0x41;
 
-//const GECKO_LIST:           u8 = 0x3D;
+const MESSAGE_SPLITTER:     u8 = 0x10;
+const GECKO_LIST:           u8 = 0x3D;
 
 // TODO not make such a mess
 // - remake Stream
@@ -31,14 +32,9 @@ struct StreamInfo {
 impl StreamInfo {
     pub fn create_event_stream<'a>(&self, code: u8, stream: &mut Stream<'a>) -> SlpResult<SubStream<'a>> {
         let sub_size = self.event_payload_sizes[code as usize];
-        if sub_size == 0 { return Err(SlpError::InvalidFile) }
+        if sub_size == 0 || sub_size as usize > stream.bytes.len() { return Err(SlpError::InvalidFile) }
         Ok(stream.sub_stream(sub_size as usize))
     }
-
-    pub fn skip_event<'a>(&self, code: u8, stream: &mut Stream<'a>) -> SlpResult<()> {
-        self.create_event_stream(code, stream)
-            .map(|_| ())
-    }
 }
 
 pub type ButtonsMask = u16;
@@ -64,6 +60,8 @@ struct PreFrameInfo {
     pub analog_trigger_value: f32,
     pub left_stick_coords: [f32; 2],
     pub right_stick_coords: [f32; 2],
+    pub raw_analog_x: i8,
+    pub rng_seed: u32,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -83,6 +81,8 @@ struct PostFrameInfo {
     pub percent: f32,
     pub is_airborne: bool,
     pub hitlag_frames: f32,
+    pub l_cancel: Option<bool>,
+    pub hurtbox_state: HurtboxState,
 }
 
 fn merge_pre_post_frames(pre: PreFrameInfo, post: PostFrameInfo) -> Frame {
@@ -102,13 +102,23 @@ fn merge_pre_post_frames(pre: PreFrameInfo, post: PostFrameInfo) -> Frame {
         analog_trigger_value: pre.analog_trigger_value,
         left_stick_coords: pre.left_stick_coords,
         right_stick_coords: pre.right_stick_coords,
+        raw_analog_x: pre.raw_analog_x,
+        rng_seed: pre.rng_seed,
         stock_count: post.stock_count,
         is_airborne: post.is_airborne,
         percent: post.percent,
         hitlag_frames: post.hitlag_frames,
+        l_cancel: post.l_cancel,
+        hurtbox_state: post.hurtbox_state,
+        nana: None,
     }
 }
 
+/// Whether a pre or post frame update is for nana rather than the main character.
+fn is_follower(bytes: &[u8]) -> bool {
+    bytes.get(0x5) == Some(&1)
+}
+
 // don't use stream - usually this is called for many files at a time
 pub fn parse_file_info(reader: &mut (impl std::io::Read + std::io::Seek)) -> SlpResult<GameInfo> {
     let mut buf = [0u8; 1024];
@@ -124,6 +134,14 @@ pub fn parse_file_info(reader: &mut (impl std::io::Read + std::io::Seek)) -> Slp
         read_count += read;
     }
 
+    if compression(&buf[0..read_count]).is_some() {
+        let mut compressed = Vec::new();
+        reader.seek(std::io::SeekFrom::Start(0)).map_err(|_| SlpError::IOError)?;
+        reader.read_to_end(&mut compressed).map_err(|_| SlpError::IOError)?;
+        let buf = decompress(&compressed)?.ok_or(SlpError::InvalidFile)?;
+        return parse_file_info(&mut std::io::Cursor::new(buf));
+    }
+
     let mut stream = Stream::new(&buf[0..read_count]);
 
     let raw_len = skip_raw_header(&mut stream)?;
@@ -143,6 +161,7 @@ pub fn parse_file_info(reader: &mut (impl std::io::Read + std::io::Seek)) -> Slp
 fn merge_metadata(game_start_info: GameStartInfo, metadata: Metadata) -> GameInfo {
     GameInfo {
         stage: game_start_info.stage,
+        players: game_start_info.players,
         low_port_idx: game_start_info.low_port_idx,
         low_starting_character: game_start_info.low_starting_character,
         high_port_idx: game_start_info.high_port_idx,
@@ -152,6 +171,15 @@ fn merge_metadata(game_start_info: GameStartInfo, metadata: Metadata) -> GameInf
         high_name: game_start_info.high_name,
         low_connect_code: game_start_info.low_connect_code,
         high_connect_code: game_start_info.high_connect_code,
+        low_nametag: game_start_info.low_nametag,
+        high_nametag: game_start_info.high_nametag,
+        random_seed: game_start_info.random_seed,
+        slippi_version: game_start_info.slippi_version,
+        is_pal: game_start_info.is_pal,
+        is_frozen_ps: game_start_info.is_frozen_ps,
+        starting_stocks: game_start_info.starting_stocks,
+        time_limit_seconds: game_start_info.time_limit_seconds,
+        items_enabled: game_start_info.items_enabled,
         duration: metadata.duration,
     }
 }
@@ -296,97 +324,572 @@ pub fn write_notes(notes: &Notes) -> Vec<u8> {
 }
 
 pub fn parse_file(stream: &mut Stream) -> SlpResult<(Game, Notes)> {
+    parse_file_filtered(stream, None)
+}
+
+/// Like `parse_file`, but only keeps frames for the player in port `port_idx` (zero indexed).
+/// Other players are still present in the game, but with no frames.
+pub fn parse_file_port(stream: &mut Stream, port_idx: u8) -> SlpResult<(Game, Notes)> {
+    parse_file_filtered(stream, Some(port_idx))
+}
+
+fn parse_file_filtered(stream: &mut Stream, only_port: Option<u8>) -> SlpResult<(Game, Notes)> {
+    if let Some(buf) = decompress(stream.as_slice())? {
+        return parse_file_filtered(&mut Stream::new(&buf), only_port);
+    }
+
     let raw_len = skip_raw_header(stream)?;
     let metadata_bytes = &stream.as_slice()[raw_len as usize..];
 
     let stream_info = parse_event_payloads(stream)?;
     let game_start_info = parse_game_start(stream, &stream_info)?;
 
-    let mut low_port_frames = Vec::new();
-    let mut high_port_frames = Vec::new();
+    if let Some(port_idx) = only_port {
+        if game_start_info.players.get(port_idx as usize).is_none_or(|p| p.is_none()) {
+            return Err(SlpError::MissingPort(port_idx));
+        }
+    }
 
-    let mut port_info: [Option<Port>; 4] = [None; 4];
-    port_info[game_start_info.low_port_idx as usize] = Some(Port::Low);
-    port_info[game_start_info.high_port_idx as usize] = Some(Port::High);
+    let mut builder = GameBuilder::new(game_start_info);
+    builder.only_port = only_port;
 
-    let mut items = Vec::new();
-    let mut item_idx = vec![0];
+    loop {
+        // replays from crashed games have no game end event
+        if stream.as_slice().is_empty() { break }
 
-    // dummy values
-    let mut pre_frame_low = PreFrameInfo { 
-        port_idx: 0,
-        buttons_mask: 0, 
-        analog_trigger_value: 0.0, 
-        right_stick_coords: [0.0; 2],
-        left_stick_coords: [0.0; 2],
+        let next_command_byte = stream.take_u8()?;
+        let event_stream = stream_info.create_event_stream(next_command_byte, stream)?;
+        if builder.handle_event(next_command_byte, event_stream.as_slice())? == EventResult::GameEnd {
+            break;
+        }
+    }
+
+    Ok(builder.finish(metadata_bytes))
+}
+
+/// Result of `validate_file`. Use `is_valid` for a single pass/fail check.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub has_game_end: bool,
+    pub last_frame: Option<i32>, // last frame bookend in the event stream
+    pub metadata_last_frame: Option<i32>, // None if the metadata is missing
+    pub human_players: u8,
+    pub cpu_players: u8,
+    pub stage: Stage,
+    pub legal_stage: bool,
+    pub missing_frames: u32, // frame numbers skipped between bookends
+}
+
+impl ValidationReport {
+    pub fn frame_count_matches(&self) -> bool {
+        self.last_frame.is_some() && self.last_frame == self.metadata_last_frame
+    }
+
+    /// Complete, uncorrupted singles replay between two humans on a legal stage.
+    pub fn is_valid(&self) -> bool {
+        self.has_game_end
+            && self.frame_count_matches()
+            && self.human_players == 2
+            && self.cpu_players == 0
+            && self.legal_stage
+            && self.missing_frames == 0
+    }
+}
+
+/// Reads the event stream once without building frames.
+/// Errors only if the file cannot be read at all.
+pub fn validate_file(stream: &mut Stream) -> SlpResult<ValidationReport> {
+    if let Some(buf) = decompress(stream.as_slice())? {
+        return validate_file(&mut Stream::new(&buf));
+    }
+
+    let raw_len = skip_raw_header(stream)?;
+    let metadata_bytes = &stream.as_slice()[raw_len as usize..];
+
+    let stream_info = parse_event_payloads(stream)?;
+    if stream.take_u8() != Ok(GAME_START) { return Err(SlpError::InvalidFile) };
+    let game_start = stream_info.create_event_stream(GAME_START, stream)?.as_slice();
+    let game_start_info = parse_game_start_payload(game_start)?;
+
+    let mut human_players = 0;
+    let mut cpu_players = 0;
+    for i in 0..4 {
+        match game_start[0x04 + 0x61 + 0x24 * i] {
+            0 => human_players += 1,
+            1 => cpu_players += 1,
+            _ => (),
+        }
+    }
+
+    let mut has_game_end = false;
+    let mut last_frame: Option<i32> = None;
+    let mut missing_frames = 0u32;
+
+    while !stream.as_slice().is_empty() {
+        let code = stream.take_u8()?;
+        let bytes = stream_info.create_event_stream(code, stream)?.as_slice();
+        match code {
+            FRAME_BOOKEND => {
+                let frame = Stream::new(bytes).take_i32()?;
+                // rollback can repeat frames, so only count forward gaps
+                if let Some(last) = last_frame {
+                    if frame > last + 1 { missing_frames += (frame - last - 1) as u32 }
+                }
+                last_frame = Some(last_frame.map_or(frame, |last| last.max(frame)));
+            }
+            GAME_END => {
+                has_game_end = true;
+                break;
+            }
+            _ => (),
+        }
+    }
+
+    let metadata = parse_metadata(metadata_bytes);
+    let metadata_last_frame = match metadata.duration {
+        u32::MAX => None,
+        d => Some(d as i32),
     };
-    let mut pre_frame_high = pre_frame_low;
-    
-    let mut stage_info = None;
 
-    loop {
-        let next_command_byte = stream.take_u8()?;
-        match next_command_byte {
+    Ok(ValidationReport {
+        has_game_end,
+        last_frame,
+        metadata_last_frame,
+        human_players,
+        cpu_players,
+        stage: game_start_info.stage,
+        legal_stage: game_start_info.stage.is_tournament_legal(),
+        missing_frames,
+    })
+}
+
+/// Parses a file without buffering it entirely.
+/// Only a single event payload is held in memory at a time,
+/// apart from the frames and items being accumulated.
+/// Compressed files are the exception, and are decompressed into memory first.
+///
+/// Returns the whole game like `parse_file`. Use `parse_reader` for a single port's actions.
+pub fn parse_file_reader(reader: &mut impl std::io::Read) -> SlpResult<(Game, Notes)> {
+    let mut header = [0u8; RAW_HEADER.len() + 4];
+    reader.read_exact(&mut header).map_err(read_err)?;
+    if compression(&header).is_some() {
+        let mut compressed = header.to_vec();
+        reader.read_to_end(&mut compressed).map_err(|_| SlpError::IOError)?;
+        let buf = decompress(&compressed)?.ok_or(SlpError::InvalidFile)?;
+        return parse_file(&mut Stream::new(&buf));
+    }
+    if !header.starts_with(RAW_HEADER) { return Err(SlpError::InvalidFile) }
+    let raw_len = u32::from_be_bytes(header[RAW_HEADER.len()..].try_into().unwrap()) as usize;
+
+    let mut payload = Vec::with_capacity(1024);
+    let (stream_info, mut raw_read) = read_event_payloads(reader)?;
+
+    if read_event(reader, &stream_info, &mut payload, &mut raw_read)? != Some(GAME_START) { return Err(SlpError::InvalidFile) }
+    let game_start_info = parse_game_start_payload(&payload)?;
+
+    let mut builder = GameBuilder::new(game_start_info);
+
+    // replays from crashed games have no game end event
+    while let Some(code) = read_event(reader, &stream_info, &mut payload, &mut raw_read)? {
+        if builder.handle_event(code, &payload)? == EventResult::GameEnd {
+            break;
+        }
+    }
+
+    // skip any events after game end, then read metadata
+    if raw_len > raw_read {
+        let skip = (raw_len - raw_read) as u64;
+        let skipped = std::io::copy(&mut std::io::Read::take(&mut *reader, skip), &mut std::io::sink())
+            .map_err(read_err)?;
+        if skipped != skip { return Err(SlpError::InvalidFile) }
+    }
+
+    let mut metadata_bytes = Vec::new();
+    reader.read_to_end(&mut metadata_bytes).map_err(read_err)?;
+
+    Ok(builder.finish(&metadata_bytes))
+}
+
+/// Number of frames from the first frame event to `lastFrame` in the metadata.
+/// The first frame is usually -123, but not always.
+/// Frame events are only scanned to the end if the metadata has no last frame, such as in crashed games.
+pub fn parse_frame_count(reader: &mut (impl std::io::Read + std::io::Seek)) -> SlpResult<u32> {
+    use std::io::{Read, SeekFrom};
+
+    let mut header = [0u8; RAW_HEADER.len() + 4];
+    reader.read_exact(&mut header).map_err(read_err)?;
+    if compression(&header).is_some() {
+        let mut compressed = header.to_vec();
+        reader.read_to_end(&mut compressed).map_err(|_| SlpError::IOError)?;
+        let buf = decompress(&compressed)?.ok_or(SlpError::InvalidFile)?;
+        return parse_frame_count(&mut std::io::Cursor::new(buf));
+    }
+    if !header.starts_with(RAW_HEADER) { return Err(SlpError::InvalidFile) }
+    let raw_len = u32::from_be_bytes(header[RAW_HEADER.len()..].try_into().unwrap()) as usize;
+
+    let mut payload = Vec::with_capacity(1024);
+    let (stream_info, mut raw_read) = read_event_payloads(reader)?;
+
+    // raw length is zero if the file was never finished
+    let mut next_frame = |reader: &mut _, raw_read: &mut usize| -> SlpResult<Option<i32>> {
+        while raw_len == 0 || *raw_read < raw_len {
+            match read_event(reader, &stream_info, &mut payload, raw_read)? {
+                Some(POST_FRAME_UPDATE) => return Ok(Some(i32::from_be_bytes(payload[0..4].try_into().unwrap()))),
+                Some(GAME_END) | None => break,
+                Some(_) => (),
+            }
+        }
+        Ok(None)
+    };
+
+    let Some(first_frame) = next_frame(reader, &mut raw_read)? else { return Ok(0) };
+
+    let mut last_frame = first_frame;
+    if raw_len != 0 {
+        reader.seek(SeekFrom::Start((header.len() + raw_len) as u64)).map_err(|_| SlpError::IOError)?;
+        let mut metadata_bytes = Vec::with_capacity(1024);
+        reader.take(1024).read_to_end(&mut metadata_bytes).map_err(read_err)?;
+        match parse_metadata(&metadata_bytes).duration {
+            u32::MAX => {
+                reader.seek(SeekFrom::Start((header.len() + raw_read) as u64)).map_err(|_| SlpError::IOError)?;
+            }
+            duration => return Ok((duration as i32 - first_frame + 1).max(0) as u32),
+        }
+    }
+
+    // rolled back frames are written again, so the last frame event is not always the latest frame
+    while let Some(frame) = next_frame(reader, &mut raw_read)? {
+        last_frame = last_frame.max(frame);
+    }
+
+    Ok((last_frame - first_frame + 1).max(0) as u32)
+}
+
+const RAW_HEADER: &[u8] = b"{U\x03raw[$U#l";
+
+fn read_u8(reader: &mut impl std::io::Read) -> SlpResult<u8> {
+    let mut b = [0u8; 1];
+    reader.read_exact(&mut b).map_err(read_err)?;
+    Ok(b[0])
+}
+
+fn read_err(e: std::io::Error) -> SlpError {
+    match e.kind() {
+        std::io::ErrorKind::UnexpectedEof => SlpError::InvalidFile,
+        _ => SlpError::IOError,
+    }
+}
+
+/// Also returns the number of raw bytes read.
+fn read_event_payloads(reader: &mut impl std::io::Read) -> SlpResult<(StreamInfo, usize)> {
+    if read_u8(reader)? != EVENT_PAYLOADS { return Err(SlpError::InvalidFile) }
+    let info_size = read_u8(reader)?;
+    let mut payload = vec![0u8; info_size as usize];
+    payload[0] = info_size;
+    reader.read_exact(&mut payload[1..]).map_err(read_err)?;
+    let stream_info = parse_event_payloads(&mut Stream::new(&[&[EVENT_PAYLOADS], &payload[..]].concat()))?;
+    Ok((stream_info, 1 + info_size as usize))
+}
+
+/// None if the reader is exhausted
+fn read_event(
+    reader: &mut impl std::io::Read, 
+    stream_info: &StreamInfo, 
+    payload: &mut Vec<u8>, 
+    raw_read: &mut usize,
+) -> SlpResult<Option<u8>> {
+    let mut b = [0u8; 1];
+    if reader.read(&mut b).map_err(read_err)? == 0 { return Ok(None) }
+    let code = b[0];
+    let size = stream_info.event_payload_sizes.get(code as usize).copied().unwrap_or(0);
+    if size == 0 { return Err(SlpError::InvalidFile) }
+    payload.resize(size as usize, 0);
+    reader.read_exact(payload).map_err(read_err)?;
+    *raw_read += 1 + size as usize;
+    Ok(Some(code))
+}
+
+/// Parses a replay that is still being written, such as by Slippi during a game.
+///
+/// Bytes can be fed in chunks of any size. 
+/// A trailing partial event is kept until the rest of it arrives.
+pub struct LiveParser {
+    pending: Vec<u8>,
+    header_read: bool,
+    stream_info: Option<StreamInfo>,
+    builder: Option<GameBuilder>,
+    game_ended: bool,
+
+    // indexed by port
+    actions: [Vec<Action>; 4],
+}
+
+impl Default for LiveParser {
+    fn default() -> Self { Self::new() }
+}
+
+impl LiveParser {
+    /// Actions ending within this many frames of the latest frame are reparsed,
+    /// as they may be extended or changed by a rollback.
+    pub const REPARSE_FRAMES: usize = 8;
+
+    pub fn new() -> Self {
+        LiveParser {
+            pending: Vec::new(),
+            header_read: false,
+            stream_info: None,
+            builder: None,
+            game_ended: false,
+            actions: Default::default(),
+        }
+    }
+
+    /// Parses every complete event in `bytes` and any previously buffered bytes.
+    /// Returns the number of frames completed by this call.
+    pub fn feed(&mut self, bytes: &[u8]) -> SlpResult<usize> {
+        let prev_frame_count = self.frame_count();
+        self.pending.extend_from_slice(bytes);
+
+        let mut consumed = 0;
+        let result = self.parse_pending(&mut consumed);
+        self.pending.drain(..consumed);
+        result?;
+
+        Ok(self.frame_count().saturating_sub(prev_frame_count))
+    }
+
+    fn parse_pending(&mut self, consumed: &mut usize) -> SlpResult<()> {
+        if self.game_ended { return Ok(()) }
+
+        if !self.header_read {
+            const HEADER: &[u8] = b"raw[$U#l";
+            let Some(i) = self.pending.windows(HEADER.len()).position(|w| w == HEADER) else { return Ok(()) };
+            // raw length is zero while the file is being written, so is not used
+            if self.pending.len() < i + HEADER.len() + 4 { return Ok(()) }
+            *consumed = i + HEADER.len() + 4;
+            self.header_read = true;
+        }
+
+        if self.stream_info.is_none() {
+            let bytes = &self.pending[*consumed..];
+            if bytes.len() < 2 { return Ok(()) }
+            let info_size = bytes[1] as usize;
+            if bytes.len() < 1 + info_size { return Ok(()) }
+            self.stream_info = Some(parse_event_payloads(&mut Stream::new(&bytes[..1 + info_size]))?);
+            *consumed += 1 + info_size;
+        }
+        let stream_info = self.stream_info.as_ref().unwrap();
+
+        loop {
+            let bytes = &self.pending[*consumed..];
+            let Some(&code) = bytes.first() else { return Ok(()) };
+            let size = stream_info.event_payload_sizes.get(code as usize).copied().unwrap_or(0) as usize;
+            if size == 0 { return Err(SlpError::InvalidFile) }
+            if bytes.len() < 1 + size { return Ok(()) }
+            let payload = &bytes[1..1 + size];
+
+            match self.builder {
+                None if code == GAME_START => {
+                    self.builder = Some(GameBuilder::new(parse_game_start_payload(payload)?));
+                }
+                None => return Err(SlpError::InvalidFile),
+                Some(ref mut builder) => {
+                    if builder.handle_event(code, payload)? == EventResult::GameEnd {
+                        self.game_ended = true;
+                    }
+                }
+            }
+
+            *consumed += 1 + size;
+            if self.game_ended { return Ok(()) }
+        }
+    }
+
+    /// None until the game start event has been read.
+    pub fn game_start_info(&self) -> Option<&GameStartInfo> {
+        self.builder.as_ref().map(|b| &b.game_start_info)
+    }
+
+    /// Whether the game end event has been read.
+    pub fn is_finished(&self) -> bool {
+        self.game_ended
+    }
+
+    /// Number of completed frames.
+    pub fn frame_count(&self) -> usize {
+        self.builder.as_ref().map_or(0, |b| b.item_idx.len() - 1)
+    }
+
+    /// Completed frames for the player in port `port_idx` (zero indexed).
+    /// Empty if the port is empty or the game has not started.
+    pub fn frames(&self, port_idx: u8) -> &[Frame] {
+        let frame_count = self.frame_count();
+        match self.builder {
+            Some(ref b) => match b.port_frames.get(port_idx as usize) {
+                Some(frames) => &frames[..frame_count.min(frames.len())],
+                None => &[],
+            }
+            None => &[],
+        }
+    }
+
+    /// Actions for the player in port `port_idx` (zero indexed), over the completed frames.
+    /// Only actions near the latest frame are reparsed.
+    /// The last action may still change as more frames arrive.
+    pub fn actions(&mut self, port_idx: u8) -> &[Action] {
+        let Some(stage) = self.game_start_info().map(|info| info.stage) else { return &[] };
+        let config = ParseConfig { stage: Some(stage), ..ParseConfig::default() };
+
+        // frames borrowed separately from the action list
+        let frame_count = self.frame_count();
+        let Some(builder) = self.builder.as_ref() else { return &[] };
+        let Some(frames) = builder.port_frames.get(port_idx as usize) else { return &[] };
+        let frames = &frames[..frame_count.min(frames.len())];
+
+        let actions = &mut self.actions[port_idx as usize];
+        let stable = frames.len().saturating_sub(Self::REPARSE_FRAMES);
+        while actions.last().is_some_and(|a| a.frame_end > stable) {
+            actions.pop();
+        }
+        let resume = actions.last().map_or(0, |a| a.frame_end);
+        actions.extend(parse_from(frames, &config, resume));
+
+        actions
+    }
+
+    /// Builds the game from the frames read so far.
+    /// Any bytes after the game end event are read as metadata.
+    pub fn finish(self) -> SlpResult<(Game, Notes)> {
+        let builder = self.builder.ok_or(SlpError::InvalidFile)?;
+        Ok(builder.finish(&self.pending))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum EventResult {
+    Continue,
+    GameEnd,
+}
+
+/// Accumulates frames, items, and stage events from individual event payloads.
+struct GameBuilder {
+    game_start_info: GameStartInfo,
+
+    // indexed by port
+    port_frames: [Vec<Frame>; 4],
+    pre_frames: [PreFrameInfo; 4],
+
+    // frames for other ports are dropped if set
+    only_port: Option<u8>,
+
+    items: Vec<Item>,
+    item_idx: Vec<u16>,
+
+    stage_info: Option<StageInfo>,
+    result: Option<GameResult>,
+    first_frame: Option<i32>,
+
+    // from frame bookends, only present in online replays.
+    // frames up to and including this will not be rolled back.
+    latest_finalized_frame: Option<i32>,
+
+    // raw gecko code list, reassembled from message splitter events
+    gecko_list: Vec<u8>,
+}
+
+impl GameBuilder {
+    pub fn new(game_start_info: GameStartInfo) -> Self {
+        // dummy values
+        let pre_frame = PreFrameInfo { 
+            port_idx: 0,
+            buttons_mask: 0, 
+            analog_trigger_value: 0.0, 
+            right_stick_coords: [0.0; 2],
+            left_stick_coords: [0.0; 2],
+            raw_analog_x: 0,
+            rng_seed: 0,
+        };
+
+        GameBuilder {
+            game_start_info,
+            port_frames: Default::default(),
+            pre_frames: [pre_frame; 4],
+            only_port: None,
+            items: Vec::new(),
+            item_idx: vec![0],
+            stage_info: None,
+            result: None,
+            first_frame: None,
+            latest_finalized_frame: None,
+            gecko_list: Vec::new(),
+        }
+    }
+
+    /// bytes do not include the command byte
+    pub fn handle_event(&mut self, code: u8, bytes: &[u8]) -> SlpResult<EventResult> {
+        match code {
             ITEM_UPDATE => {
-                items.push(parse_item_update(stream, &stream_info)?);
+                self.items.push(parse_item_update(bytes)?);
             }
             PRE_FRAME_UPDATE => {
-                let pre_frame = parse_pre_frame_info(stream, &stream_info)?;
-                let port = port_info[pre_frame.port_idx as usize].unwrap();
-                match port {
-                    Port::Low => pre_frame_low = pre_frame,
-                    Port::High => pre_frame_high = pre_frame,
-                }
+                // nana's inputs are copied from popo
+                if is_follower(bytes) { return Ok(EventResult::Continue) }
+
+                let pre_frame = parse_pre_frame_info(bytes)?;
+                let port_idx = self.port_idx(pre_frame.port_idx)?;
+                self.pre_frames[port_idx] = pre_frame;
             }
             POST_FRAME_UPDATE => {
-                let post_frame = parse_post_frame_info(stream, &stream_info)?;
-
-                let port = port_info[post_frame.port_idx as usize].unwrap();
+                let post_frame = parse_post_frame_info(bytes)?;
+                if self.first_frame.is_none() {
+                    // usually -123, but not always
+                    self.first_frame = Some(i32::from_be_bytes(bytes[0..4].try_into().unwrap()));
+                }
 
-                match port {
-                    Port::Low => low_port_frames.push(merge_pre_post_frames(pre_frame_low, post_frame)),
-                    Port::High => high_port_frames.push(merge_pre_post_frames(pre_frame_high, post_frame)),
+                let port_idx = self.port_idx(post_frame.port_idx)?;
+                if self.only_port.is_some_and(|p| p as usize != port_idx) { return Ok(EventResult::Continue) }
+
+                // nana's post frame follows popo's for the same frame
+                if is_follower(bytes) {
+                    if let Some(popo) = self.port_frames[port_idx].last_mut() {
+                        popo.nana = Some(NanaFrame {
+                            direction: post_frame.direction,
+                            velocity: post_frame.velocity,
+                            position: post_frame.position,
+                            state: post_frame.state,
+                            state_num: post_frame.state_num,
+                            percent: post_frame.percent,
+                            is_airborne: post_frame.is_airborne,
+                        });
+                    }
+                    return Ok(EventResult::Continue);
                 }
+
+                self.port_frames[port_idx].push(merge_pre_post_frames(self.pre_frames[port_idx], post_frame));
+            }
+            FRAME_START => {
+                // events from a frame that was never finished were superseded by a rollback
+                self.discard_unfinished_frame();
             }
             FRAME_BOOKEND => {
-                let mut stream = stream_info.create_event_stream(FRAME_BOOKEND, stream)?;
-                let frame_num = (stream.take_i32()? + 123) as usize; // slippi starts frames at -123
-
-                // rollback :(
-                if frame_num + 1 as usize != low_port_frames.len() {
-                    low_port_frames[frame_num] = low_port_frames[low_port_frames.len()-1];
-                    high_port_frames[frame_num] = high_port_frames[low_port_frames.len()-1];
-                    low_port_frames.truncate(frame_num+1);
-                    high_port_frames.truncate(frame_num+1);
-
-                    // TODO untested eek
-                    let item_idx_restart = item_idx[frame_num] as usize;
-                    let item_start_this_frame = item_idx[item_idx.len()-1] as usize;
-                    let item_count_this_frame = items.len() - item_start_this_frame;
-                    for i in 0..item_count_this_frame {
-                        items[item_idx_restart+i] = items[item_start_this_frame+i];
-                    }
-                    items.truncate(item_idx_restart+item_count_this_frame);
-                    if item_idx.len() == frame_num+1 {
-                        item_idx.push(items.len() as _);
-                    } else {
-                        item_idx[frame_num+1] = items.len() as _;
-                        item_idx.truncate(frame_num+2);
-                    }
-                } else {
-                    item_idx.push(items.len() as _);
+                let mut stream = Stream::new(bytes);
+                let first_frame = self.first_frame.ok_or(SlpError::InvalidFile)?;
+                let frame_num = (stream.take_i32()? - first_frame) as usize;
+                if let Ok(finalized) = stream.take_i32() {
+                    self.latest_finalized_frame = Some(finalized);
                 }
+                self.finish_frame(frame_num);
             }
             FOD_INFO => {
-                let fountain_heights = match stage_info {
+                let fountain_heights = match self.stage_info {
                     Some(StageInfo::Fountain(ref mut heights)) => heights,
                     None => {
-                        stage_info = Some(StageInfo::Fountain(FountainHeights {
+                        self.stage_info = Some(StageInfo::Fountain(FountainHeights {
                             heights_l: Vec::new(),
                             heights_r: Vec::new(),
                         }));
 
-                        match stage_info {
+                        match self.stage_info {
                             Some(StageInfo::Fountain(ref mut heights)) => heights,
                             _ => unreachable!(),
                         }
@@ -394,7 +897,7 @@ pub fn parse_file(stream: &mut Stream) -> SlpResult<(Game, Notes)> {
                     _ => unreachable!(),
                 };
 
-                let mut stream = stream_info.create_event_stream(FOD_INFO, stream)?;
+                let mut stream = Stream::new(bytes);
                 let frame = stream.take_i32()?;
                 let plat = stream.take_u8()?;
                 let height = stream.take_float()?;
@@ -406,18 +909,17 @@ pub fn parse_file(stream: &mut Stream) -> SlpResult<(Game, Notes)> {
                 };
             }
             DREAMLAND_INFO => {
-                let mut _stream = stream_info.create_event_stream(DREAMLAND_INFO, stream)?;
-                //println!("dreamland: {:x?}", stream.bytes);
+                //println!("dreamland: {:x?}", bytes);
             }
             STADIUM_INFO => {
-                let transformations = match stage_info {
+                let transformations = match self.stage_info {
                     Some(StageInfo::Stadium(ref mut transformations)) => transformations,
                     None => {
-                        stage_info = Some(StageInfo::Stadium(StadiumTransformations {
+                        self.stage_info = Some(StageInfo::Stadium(StadiumTransformations {
                             events: Vec::new(),
                         }));
 
-                        match stage_info {
+                        match self.stage_info {
                             Some(StageInfo::Stadium(ref mut transformations)) => transformations,
                             _ => unreachable!(),
                         }
@@ -425,7 +927,7 @@ pub fn parse_file(stream: &mut Stream) -> SlpResult<(Game, Notes)> {
                     _ => unreachable!(),
                 };
 
-                let mut stream = stream_info.create_event_stream(STADIUM_INFO, stream)?;
+                let mut stream = Stream::new(bytes);
                 let frame = stream.take_i32()?;
                 let event = stream.take_u16()?;
                 let transformation_id = stream.take_u16()?;
@@ -444,24 +946,217 @@ pub fn parse_file(stream: &mut Stream) -> SlpResult<(Game, Notes)> {
                     transformations.events.push((frame, transformation));
                 }
             }
-            GAME_END => break,
-            _ => {
-                stream_info.skip_event(next_command_byte, stream)?;
+            MESSAGE_SPLITTER => {
+                if bytes.len() < 0x204 { return Err(SlpError::InvalidFile) }
+                let size = u16::from_be_bytes(bytes[0x200..0x202].try_into().unwrap()) as usize;
+                if bytes[0x202] == GECKO_LIST {
+                    self.gecko_list.extend_from_slice(&bytes[..size.min(0x200)]);
+                }
             }
+            GECKO_LIST => {
+                self.gecko_list.extend_from_slice(bytes);
+            }
+            GAME_END => {
+                self.result = Some(parse_game_end(bytes)?);
+                return Ok(EventResult::GameEnd);
+            }
+            _ => (),
         }
+
+        Ok(EventResult::Continue)
     }
 
-    let metadata = parse_metadata(metadata_bytes);
-    let notes = parse_notes(metadata_bytes);
+    /// Errors if the port is empty
+    fn port_idx(&self, port_idx: u8) -> SlpResult<usize> {
+        match self.game_start_info.players.get(port_idx as usize) {
+            Some(Some(_)) => Ok(port_idx as usize),
+            _ => Err(SlpError::InvalidFile),
+        }
+    }
+
+    fn finish_frame(&mut self, frame_num: usize) {
+        let items = &mut self.items;
+        let item_idx = &mut self.item_idx;
+        // the low port may be filtered out
+        let frame_count = self.port_frames.iter().map(|f| f.len()).max().unwrap_or(0);
+
+        // rollback :(
+        if frame_num + 1 as usize != frame_count {
+            for frames in self.port_frames.iter_mut().filter(|f| !f.is_empty()) {
+                frames[frame_num] = frames[frame_count-1];
+                frames.truncate(frame_num+1);
+            }
 
-    Ok((Game {
-        high_port_frames: high_port_frames.into_boxed_slice(), 
-        low_port_frames: low_port_frames.into_boxed_slice(),
-        item_idx: item_idx.into_boxed_slice(),
-        items: items.into_boxed_slice(),
-        info: merge_metadata(game_start_info, metadata),
-        stage_info,
-    }, notes))
+            // TODO untested eek
+            let item_idx_restart = item_idx[frame_num] as usize;
+            let item_start_this_frame = item_idx[item_idx.len()-1] as usize;
+            let item_count_this_frame = items.len() - item_start_this_frame;
+            for i in 0..item_count_this_frame {
+                items[item_idx_restart+i] = items[item_start_this_frame+i];
+            }
+            items.truncate(item_idx_restart+item_count_this_frame);
+            if item_idx.len() == frame_num+1 {
+                item_idx.push(items.len() as _);
+            } else {
+                item_idx[frame_num+1] = items.len() as _;
+                item_idx.truncate(frame_num+2);
+            }
+        } else {
+            item_idx.push(items.len() as _);
+        }
+    }
+
+    /// Frames without a bookend yet.
+    fn discard_unfinished_frame(&mut self) {
+        let finished = self.item_idx.len() - 1;
+        self.truncate_frames(finished);
+    }
+
+    fn truncate_frames(&mut self, frame_count: usize) {
+        let frame_count = frame_count.min(self.item_idx.len() - 1);
+        for frames in self.port_frames.iter_mut() {
+            frames.truncate(frame_count);
+        }
+        self.item_idx.truncate(frame_count + 1);
+        self.items.truncate(self.item_idx[frame_count] as usize);
+    }
+
+    pub fn finish(mut self, metadata_bytes: &[u8]) -> (Game, Notes) {
+        // every port has exactly one frame per frame number
+        self.discard_unfinished_frame();
+
+        // replays from crashed games may end before rollbacks are resolved
+        if self.result.is_none() {
+            if let (Some(first), Some(finalized)) = (self.first_frame, self.latest_finalized_frame) {
+                let finalized_count = (finalized - first + 1).max(0) as usize;
+                self.truncate_frames(finalized_count);
+            }
+        }
+
+        let metadata = parse_metadata(metadata_bytes);
+        let notes = parse_notes(metadata_bytes);
+
+        let mut result = self.result.unwrap_or(GameResult {
+            end_method: GameEndMethod::Unresolved,
+            winner_port: None,
+            lras_initiator: None,
+        });
+
+        let low_idx = self.game_start_info.low_port_idx;
+        let high_idx = self.game_start_info.high_port_idx;
+        let is_singles = self.game_start_info.players.iter().flatten().count() == 2;
+
+        // older replays have no placements, so determine the winner from the final frame
+        if result.winner_port.is_none() && is_singles {
+            let low_frames = &self.port_frames[low_idx as usize];
+            let high_frames = &self.port_frames[high_idx as usize];
+            if let (Some(low), Some(high)) = (low_frames.last(), high_frames.last()) {
+                result.winner_port = match result.end_method {
+                    GameEndMethod::Game | GameEndMethod::Time if low.stock_count != high.stock_count => {
+                        Some(if low.stock_count > high.stock_count { low_idx } else { high_idx })
+                    }
+                    GameEndMethod::Time if low.percent != high.percent => {
+                        Some(if low.percent < high.percent { low_idx } else { high_idx })
+                    }
+                    _ => None,
+                };
+            }
+        }
+
+        let gecko_codes = parse_gecko_codes(&self.gecko_list);
+
+        let players = self.port_frames.into_iter()
+            .zip(self.game_start_info.players.iter())
+            .filter_map(|(frames, player)| Some(PlayerFrames {
+                port_idx: player.as_ref()?.port_idx,
+                frames: frames.into_boxed_slice(),
+            }))
+            .collect();
+
+        (Game {
+            players,
+            item_idx: self.item_idx.into_boxed_slice(),
+            items: self.items.into_boxed_slice(),
+            info: merge_metadata(self.game_start_info, metadata),
+            stage_info: self.stage_info,
+            result,
+            first_frame: self.first_frame.unwrap_or(Game::DEFAULT_FIRST_FRAME),
+            gecko_codes,
+        }, notes)
+    }
+}
+
+/// Returns the first word of each code in a raw gecko code list.
+/// Stops at the end of list code, or where the length of a code cannot be read.
+fn parse_gecko_codes(bytes: &[u8]) -> Vec<u32> {
+    let word = |i: usize| bytes.get(i..i+4).map(|b| u32::from_be_bytes(b.try_into().unwrap()));
+
+    let mut codes = Vec::new();
+    let mut i = 0;
+    while let Some(code) = word(i) {
+        // lowest bit selects the base address, not the code type
+        let code_len = match (code >> 24) & 0xFE {
+            0xF0 => break, // end of list
+            0xC0 | 0xC2 => {
+                let Some(line_count) = word(i + 4) else { break };
+                8 + line_count as usize * 8
+            }
+            0x06 => {
+                let Some(byte_count) = word(i + 4) else { break };
+                8 + ((byte_count as usize + 7) & !7)
+            }
+            0x08 => 16,
+            _ => 8,
+        };
+
+        codes.push(code);
+        i += code_len;
+    }
+
+    codes
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Zlib,
+}
+
+/// Checks for a gzip or zlib header.
+pub fn compression(buf: &[u8]) -> Option<Compression> {
+    match buf {
+        [0x1F, 0x8B, ..] => Some(Compression::Gzip),
+        [0x78, flg, ..] if u16::from_be_bytes([0x78, *flg]).is_multiple_of(31) => Some(Compression::Zlib),
+        _ => None,
+    }
+}
+
+/// Returns None if the buffer is not compressed.
+/// Requires the flate2 feature, otherwise compressed buffers return `SlpError::CompressedFile`.
+pub fn decompress(buf: &[u8]) -> SlpResult<Option<Vec<u8>>> {
+    let compression = match compression(buf) {
+        Some(c) => c,
+        None => return Ok(None),
+    };
+
+    #[cfg(feature = "flate2")]
+    {
+        use std::io::Read;
+
+        let mut decompressed = Vec::new();
+        let res = match compression {
+            Compression::Gzip => flate2::read::GzDecoder::new(buf).read_to_end(&mut decompressed),
+            Compression::Zlib => flate2::read::ZlibDecoder::new(buf).read_to_end(&mut decompressed),
+        };
+        res.map_err(|_| SlpError::InvalidFile)?;
+        Ok(Some(decompressed))
+    }
+
+    #[cfg(not(feature = "flate2"))]
+    {
+        let _ = compression;
+        Err(SlpError::CompressedFile)
+    }
 }
 
 pub fn skip_raw_header(stream: &mut Stream) -> SlpResult<u32> {
@@ -480,7 +1175,10 @@ fn parse_game_start(stream: &mut Stream, info: &StreamInfo) -> SlpResult<GameSta
     // note: takes a byte here
     if stream.take_u8() != Ok(GAME_START) { return Err(SlpError::InvalidFile) };
     let substream = info.create_event_stream(GAME_START, stream)?;
-    let bytes = substream.as_slice();
+    parse_game_start_payload(substream.as_slice())
+}
+
+fn parse_game_start_payload(bytes: &[u8]) -> SlpResult<GameStartInfo> {
 
     // requires version >= 3.14.0
     if bytes[0] < MIN_VERSION_MAJOR || 
@@ -492,32 +1190,44 @@ fn parse_game_start(stream: &mut Stream, info: &StreamInfo) -> SlpResult<GameSta
     let stage_start = 0x4 + 0xE;
     let stage = u16::from_be_bytes(bytes[stage_start..(stage_start+2)].try_into().unwrap());
     let stage = Stage::from_u16(stage)
-        .ok_or(SlpError::InvalidFile)?;
+        .ok_or(SlpError::UnknownStage(stage))?;
 
     let mut port_types = [0u8; 4];
     for i in 0..4 {
         port_types[i] = bytes[0x04 + 0x61 + 0x24 * i];
     }
 
-    let mut port_iter = port_types.iter().enumerate().filter_map(|(i,p)| if *p != 3 { Some(i) } else { None });
-    let low_port_idx = port_iter.next().ok_or(SlpError::NotTwoPlayers)? as _;
-    let high_port_idx = port_iter.next().ok_or(SlpError::NotTwoPlayers)? as _;
-    if port_iter.next().is_some() { return Err(SlpError::NotTwoPlayers) }
+    let is_teams = bytes[0x04 + 0x08] != 0;
+
+    let mut players = [None; 4];
+    for i in 0..4 {
+        if port_types[i] == 3 { continue }
 
-    let low_char_idx  = bytes[0x04 + 0x60 + 0x24 * low_port_idx as usize];
-    let low_colour_idx = bytes[0x04 + 0x63 + 0x24 * low_port_idx as usize];
-    let high_char_idx = bytes[0x04 + 0x60 + 0x24 * high_port_idx as usize];
-    let high_colour_idx = bytes[0x04 + 0x63 + 0x24 * high_port_idx as usize];
-    let low_char  = Character::from_u8_external(low_char_idx)
-        .ok_or(SlpError::InvalidFile)?;
-    let high_char = Character::from_u8_external(high_char_idx)
-        .ok_or(SlpError::InvalidFile)?;
+        let char_idx = bytes[0x04 + 0x60 + 0x24 * i];
+        let colour_idx = bytes[0x04 + 0x63 + 0x24 * i];
+        let character = Character::from_u8_external(char_idx)
+            .ok_or(SlpError::UnknownCharacter(char_idx))?;
 
-    // mods can add more colour indices, so replace with neutral colour
-    let low_starting_character  = CharacterColour::from_character_and_colour(low_char, low_colour_idx)
-        .unwrap_or_else(|| CharacterColour::from_character_and_colour(low_char, 0).unwrap());
-    let high_starting_character = CharacterColour::from_character_and_colour(high_char, high_colour_idx)
-        .unwrap_or_else(|| CharacterColour::from_character_and_colour(high_char, 0).unwrap());
+        // mods can add more colour indices, so replace with neutral colour
+        let starting_character = CharacterColour::from_character_and_colour(character, colour_idx)
+            .unwrap_or_else(|| CharacterColour::from_character_and_colour(character, 0).unwrap());
+
+        let team_id = if is_teams { Some(bytes[0x04 + 0x69 + 0x24 * i]) } else { None };
+
+        players[i] = Some(PlayerInfo {
+            port_idx: i as u8,
+            starting_character,
+            team_id,
+        });
+    }
+
+    let mut port_iter = players.iter().flatten();
+    let low = *port_iter.next().ok_or(SlpError::NotTwoPlayers)?;
+    let high = *port_iter.next().ok_or(SlpError::NotTwoPlayers)?;
+    let low_port_idx = low.port_idx;
+    let high_port_idx = high.port_idx;
+    let low_starting_character = low.starting_character;
+    let high_starting_character = high.starting_character;
 
     let low_name_offset = 0x1A5 + 0x1F * low_port_idx as usize - 1;
     let low_name = bytes[low_name_offset..low_name_offset+32].try_into().unwrap();
@@ -529,11 +1239,33 @@ fn parse_game_start(stream: &mut Stream, info: &StreamInfo) -> SlpResult<GameSta
     let high_code_offset = 0x221 + 0x0A * high_port_idx as usize - 1;
     let high_connect_code = bytes[high_code_offset..high_code_offset+10].try_into().unwrap();
 
+    let low_tag_offset = 0x161 + 0x10 * low_port_idx as usize - 1;
+    let low_nametag = bytes[low_tag_offset..low_tag_offset+16].try_into().unwrap();
+    let high_tag_offset = 0x161 + 0x10 * high_port_idx as usize - 1;
+    let high_nametag = bytes[high_tag_offset..high_tag_offset+16].try_into().unwrap();
+
+    let random_seed = u32::from_be_bytes(bytes[0x13C..0x140].try_into().unwrap());
+
+    let slippi_version = (bytes[0], bytes[1], bytes[2]);
+
+    // both flags exist in every supported version, but default to ntsc and not frozen regardless
+    let is_pal = bytes.get(0x1A0).is_some_and(|b| *b != 0);
+    let is_frozen_ps = bytes.get(0x1A1).is_some_and(|b| *b != 0);
+
+    let starting_stocks = bytes[0x04 + 0x62 + 0x24 * low_port_idx as usize];
+    let time_limit_seconds = match u32::from_be_bytes(bytes[0x14..0x18].try_into().unwrap()) {
+        0 => None,
+        t => Some(t),
+    };
+    // item spawn behaviour is -1 when items are off
+    let items_enabled = bytes[0x0F] as i8 != -1;
+
     //let timestamp = &bytes[(0x04 + 0x2BE)..(0x04 + 0x2BE + 51)];
     //let start_time = parse_timestamp(timestamp)?;
 
     Ok(GameStartInfo {
         stage, 
+        players,
         low_port_idx, 
         low_starting_character,
         high_port_idx,
@@ -542,6 +1274,15 @@ fn parse_game_start(stream: &mut Stream, info: &StreamInfo) -> SlpResult<GameSta
         high_name,
         low_connect_code,
         high_connect_code,
+        low_nametag,
+        high_nametag,
+        random_seed,
+        slippi_version,
+        is_pal,
+        is_frozen_ps,
+        starting_stocks,
+        time_limit_seconds,
+        items_enabled,
     })
 }
 
@@ -575,11 +1316,34 @@ fn parse_timestamp(timestamp: &[u8]) -> SlpResult<Time> {
     Ok(Time(time))
 }
 
-fn parse_item_update(stream: &mut Stream, info: &StreamInfo) -> SlpResult<Item> {
-    // note: takes a byte here
-    let substream = info.create_event_stream(ITEM_UPDATE, stream)?;
-    let bytes = substream.as_slice();
+fn parse_game_end(bytes: &[u8]) -> SlpResult<GameResult> {
+    let end_method = match bytes.first().ok_or(SlpError::InvalidFile)? {
+        1 => GameEndMethod::Time,
+        2 => GameEndMethod::Game,
+        3 => GameEndMethod::Resolved,
+        7 => GameEndMethod::NoContest,
+        _ => GameEndMethod::Unresolved,
+    };
+
+    // added in 2.0.0
+    let lras_initiator = match bytes.get(1).map(|b| *b as i8) {
+        Some(-1) | None => None,
+        Some(port_idx) => Some(port_idx as u8),
+    };
+
+    // added in 3.13.0
+    let winner_port = bytes.get(2..6)
+        .and_then(|placements| placements.iter().position(|p| *p == 0))
+        .map(|port_idx| port_idx as u8);
 
+    Ok(GameResult {
+        end_method,
+        winner_port,
+        lras_initiator,
+    })
+}
+
+fn parse_item_update(bytes: &[u8]) -> SlpResult<Item> {
     if bytes.len() < 0x2A {
         return Err(SlpError::InvalidFile);
     }
@@ -592,11 +1356,18 @@ fn parse_item_update(stream: &mut Stream, info: &StreamInfo) -> SlpResult<Item>
         x: f32::from_be_bytes(bytes[0x13..0x17].try_into().unwrap()),
         y: f32::from_be_bytes(bytes[0x17..0x1B].try_into().unwrap()),
     };
+    let velocity = Vector {
+        x: f32::from_be_bytes(bytes[0x0B..0x0F].try_into().unwrap()),
+        y: f32::from_be_bytes(bytes[0x0F..0x13].try_into().unwrap()),
+    };
     let missile_type = bytes[0x25];
     let turnip_type = bytes[0x26];
     let charge_shot_launched = bytes[0x27] == 1;
     let charge_shot_power = bytes[0x28];
-    let owner = bytes[0x29] as i8;
+    let owner = match bytes[0x29] as i8 {
+        -1 => None,
+        port_idx => Some(port_idx as u8),
+    };
     let spawn_id = u32::from_be_bytes(bytes[0x21..0x25].try_into().unwrap());
 
     Ok(Item {
@@ -604,6 +1375,7 @@ fn parse_item_update(stream: &mut Stream, info: &StreamInfo) -> SlpResult<Item>
         state,
         direction,
         position,
+        velocity,
         missile_type,
         turnip_type,
         charge_shot_launched,
@@ -613,15 +1385,13 @@ fn parse_item_update(stream: &mut Stream, info: &StreamInfo) -> SlpResult<Item>
     })
 }
 
-fn parse_pre_frame_info(stream: &mut Stream, info: &StreamInfo) -> SlpResult<PreFrameInfo> {
-    let substream = info.create_event_stream(PRE_FRAME_UPDATE, stream)?;
-    let bytes = substream.as_slice();
-
-    if bytes.len() < 0x2C {
+fn parse_pre_frame_info(bytes: &[u8]) -> SlpResult<PreFrameInfo> {
+    if bytes.len() < 0x32 {
         return Err(SlpError::InvalidFile);
     }
 
     let port_idx = bytes[0x4];
+    let rng_seed = u32::from_be_bytes(bytes[0x6..0xA].try_into().unwrap());
     let analog_trigger_value = f32::from_be_bytes(bytes[0x28..0x2C].try_into().unwrap());
     let left_stick_coords = [
         f32::from_be_bytes(bytes[0x18..0x1C].try_into().unwrap()),
@@ -634,26 +1404,28 @@ fn parse_pre_frame_info(stream: &mut Stream, info: &StreamInfo) -> SlpResult<Pre
 
     let buttons_mask = u16::from_be_bytes(bytes[0x30..0x32].try_into().unwrap());
 
+    // added in 1.2.0
+    let raw_analog_x = bytes.get(0x3A).map(|b| *b as i8).unwrap_or(0);
+
     Ok(PreFrameInfo {
         port_idx,
         buttons_mask,
         analog_trigger_value,
         left_stick_coords,
         right_stick_coords,
+        raw_analog_x,
+        rng_seed,
     })
 }
 
-fn parse_post_frame_info(stream: &mut Stream, info: &StreamInfo) -> SlpResult<PostFrameInfo> {
-    let substream = info.create_event_stream(POST_FRAME_UPDATE, stream)?;
-    let bytes = substream.as_slice();
-
+fn parse_post_frame_info(bytes: &[u8]) -> SlpResult<PostFrameInfo> {
     if bytes.len() < 0x44 {
         return Err(SlpError::InvalidFile);
     }
 
     let port_idx = bytes[0x4];
     let character = Character::from_u8_internal(bytes[0x6])
-        .ok_or(SlpError::InvalidFile)?;
+        .ok_or(SlpError::UnknownCharacter(bytes[0x6]))?;
 
     let direction_f = f32::from_be_bytes(bytes[0x11..0x15].try_into().unwrap());
     let direction = if direction_f == 1.0 { Direction::Right } else { Direction::Left };
@@ -679,6 +1451,16 @@ fn parse_post_frame_info(stream: &mut Stream, info: &StreamInfo) -> SlpResult<Po
     let anim_frame = f32::from_be_bytes(bytes[0x21..0x25].try_into().unwrap());
     let hitlag_frames = f32::from_be_bytes(bytes[0x48..0x4C].try_into().unwrap());
     let is_airborne = bytes[0x2E] == 1;
+    let l_cancel = match bytes[0x32] {
+        1 => Some(true),
+        2 => Some(false),
+        _ => None,
+    };
+    let hurtbox_state = match bytes[0x33] {
+        1 => HurtboxState::Invulnerable,
+        2 => HurtboxState::Intangible,
+        _ => HurtboxState::Vulnerable,
+    };
 
     Ok(PostFrameInfo {
         port_idx,
@@ -696,6 +1478,8 @@ fn parse_post_frame_info(stream: &mut Stream, info: &StreamInfo) -> SlpResult<Po
         is_airborne,
         percent,
         hitlag_frames,
+        l_cancel,
+        hurtbox_state,
     })
 }
 
@@ -796,3 +1580,154 @@ impl<'a> Stream<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::*;
+
+    fn replay(first_frame: i32, last_frame: i32) -> ReplayBuilder {
+        let f = frame(StandardActionState::Wait);
+        (first_frame..=last_frame).fold(ReplayBuilder::new(), |r, n| r.frame(n, [&f, &f]))
+    }
+
+    fn frame_count(bytes: Vec<u8>) -> u32 {
+        parse_frame_count(&mut std::io::Cursor::new(bytes)).unwrap()
+    }
+
+    #[test]
+    fn frame_count_from_metadata() {
+        let bytes = replay(-123, 20).game_end().last_frame(20).build();
+        assert_eq!(frame_count(bytes.clone()), 144);
+
+        let (game, _) = parse_file(&mut Stream::new(&bytes)).unwrap();
+        assert_eq!(game.port_frames(Port::Low).len(), 144);
+    }
+
+    #[test]
+    fn frame_count_from_late_first_frame() {
+        let bytes = replay(-39, 20).game_end().last_frame(20).build();
+        assert_eq!(frame_count(bytes), 60);
+    }
+
+    #[test]
+    fn frame_count_without_metadata() {
+        // crashed games have no game end or last frame
+        let f = frame(StandardActionState::Wait);
+        let bytes = replay(-39, 20)
+            .frame_finalized(15, 12, [&f, &f])
+            .build();
+        assert_eq!(frame_count(bytes), 60);
+    }
+
+    fn game_with_rollback() -> Vec<u8> {
+        let mut f = frame(StandardActionState::Wait);
+        let mut r = ReplayBuilder::new();
+        for n in -123..=10 {
+            f.position.x = n as f32;
+            r = r.frame_finalized(n, n - 3, [&f, &f]);
+        }
+        f.position.x = 100.0;
+        r.frame_finalized(8, 7, [&f, &f])
+            .game_end()
+            .last_frame(10)
+            .build()
+    }
+
+    #[test]
+    fn reader_matches_buffer() {
+        let bytes = game_with_rollback();
+        let from_reader = parse_file_reader(&mut std::io::Cursor::new(&bytes)).unwrap();
+        let from_buffer = parse_file(&mut Stream::new(&bytes)).unwrap();
+        assert_eq!(format!("{:?}", from_reader), format!("{:?}", from_buffer));
+    }
+
+    #[test]
+    fn reader_requires_exact_header() {
+        let mut bytes = game_with_rollback();
+        bytes.insert(1, b'x');
+        let res = parse_file_reader(&mut std::io::Cursor::new(&bytes));
+        assert_eq!(res.err(), Some(SlpError::InvalidFile));
+    }
+
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn reader_decompresses() {
+        use std::io::Write;
+
+        let bytes = game_with_rollback();
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&bytes).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let from_reader = parse_file_reader(&mut std::io::Cursor::new(&compressed)).unwrap();
+        let from_buffer = parse_file(&mut Stream::new(&bytes)).unwrap();
+        assert_eq!(format!("{:?}", from_reader), format!("{:?}", from_buffer));
+    }
+
+    #[test]
+    fn detects_compression() {
+        let bytes = game_with_rollback();
+        assert_eq!(compression(&bytes), None);
+        assert_eq!(compression(&[0x1F, 0x8B, 0x08]), Some(Compression::Gzip));
+        assert_eq!(compression(&[0x78, 0x9C]), Some(Compression::Zlib));
+        assert_eq!(compression(&[0x78, 0x00]), None);
+    }
+
+    #[cfg(not(feature = "flate2"))]
+    #[test]
+    fn compressed_requires_flate2() {
+        let res = parse_file(&mut Stream::new(&[0x1F, 0x8B, 0x08, 0x00]));
+        assert_eq!(res.err(), Some(SlpError::CompressedFile));
+    }
+
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn compressed_round_trip() {
+        use std::io::Write;
+
+        let bytes = game_with_rollback();
+        let expected = format!("{:?}", parse_file(&mut Stream::new(&bytes)).unwrap());
+
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip.write_all(&bytes).unwrap();
+        let gzip = gzip.finish().unwrap();
+        assert_eq!(format!("{:?}", parse_file(&mut Stream::new(&gzip)).unwrap()), expected);
+
+        let mut zlib = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        zlib.write_all(&bytes).unwrap();
+        let zlib = zlib.finish().unwrap();
+        assert_eq!(format!("{:?}", parse_file(&mut Stream::new(&zlib)).unwrap()), expected);
+
+        let info = parse_file_info(&mut std::io::Cursor::new(&zlib)).unwrap();
+        assert_eq!(info.duration, 10);
+    }
+
+    #[test]
+    fn rollback_replaces_frames() {
+        let mut f = frame(StandardActionState::Wait);
+        let mut r = ReplayBuilder::new();
+        for n in -123..=10 {
+            f.position.x = n as f32;
+            r = r.frame_finalized(n, n - 7, [&f, &f]);
+        }
+        // frames 8 to 10 are predicted wrong, then resent.
+        // the first resent frame is interrupted by another rollback before it finishes
+        f.position.x = -1000.0;
+        r = r.unfinished_frame(8, [&f, &f]);
+        for n in 8..=10 {
+            f.position.x = 100.0 + n as f32;
+            r = r.frame_finalized(n, 3, [&f, &f]);
+        }
+        let bytes = r.game_end().last_frame(10).build();
+
+        let (game, _) = parse_file(&mut Stream::new(&bytes)).unwrap();
+        let frames = game.port_frames(Port::Low);
+        assert_eq!(frames.len(), 134);
+        for (i, f) in frames.iter().enumerate() {
+            let n = i as i32 - 123;
+            let x = if n >= 8 { 100.0 + n as f32 } else { n as f32 };
+            assert_eq!(f.position.x, x, "frame {}", n);
+        }
+    }
+}
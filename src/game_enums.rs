@@ -1,4 +1,7 @@
+use crate::{Vector, StadiumTransformation};
+
 #[derive(Hash, Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Stage {
     FountainOfDreams     = 002,
     PokemonStadium       = 003,
@@ -32,7 +35,14 @@ pub enum Stage {
 }
 
 impl Stage {
+    /// Same as `from_external_id`.
     pub fn from_u16(st: u16) -> Option<Self> {
+        Self::from_external_id(st)
+    }
+
+    /// External ids are the stage ids Slippi writes in the game start event, and the `Stage` discriminants.
+    /// Internal ids (`to_u16_internal`) are the in-game stage kinds, which use a different ordering.
+    pub fn from_external_id(st: u16) -> Option<Self> {
         Some(match st {
             002 => Stage::FountainOfDreams    ,
             003 => Stage::PokemonStadium      ,
@@ -148,10 +158,188 @@ impl Stage {
             _ => "Illegal",
         }
     }
+
+    pub fn is_tournament_legal(self) -> bool {
+        matches!(self,
+            Stage::FountainOfDreams | Stage::PokemonStadium | Stage::YoshisStory
+            | Stage::DreamLandN64 | Stage::Battlefield | Stage::FinalDestination
+        )
+    }
+
+    /// None for non tournament legal stages.
+    pub fn blast_zones(self) -> Option<BlastZones> {
+        let (left, right, top, bottom) = match self {
+            Stage::FountainOfDreams => (-198.75, 198.75, 202.5, -146.25),
+            Stage::PokemonStadium   => (-230.0, 230.0, 180.0, -111.0),
+            Stage::YoshisStory      => (-175.7, 173.6, 168.0, -91.0),
+            Stage::DreamLandN64     => (-255.0, 255.0, 250.0, -123.0),
+            Stage::Battlefield      => (-224.0, 224.0, 200.0, -108.8),
+            Stage::FinalDestination => (-246.0, 246.0, 188.0, -140.0),
+            _ => return None,
+        };
+
+        Some(BlastZones { left, right, top, bottom })
+    }
+
+    /// Positions of the left and right ledges.
+    /// None for non tournament legal stages.
+    pub fn ledges(self) -> Option<[Vector; 2]> {
+        let (x, y) = match self {
+            Stage::FountainOfDreams => (63.35, 0.62),
+            Stage::PokemonStadium   => (87.75, 0.0),
+            Stage::YoshisStory      => (56.0, -3.5),
+            Stage::DreamLandN64     => (77.27, 0.0),
+            Stage::Battlefield      => (68.4, 0.0),
+            Stage::FinalDestination => (85.5657, 0.0),
+            _ => return None,
+        };
+
+        Some([Vector { x: -x, y }, Vector { x, y }])
+    }
+
+    /// Empty for stages without platforms and non tournament legal stages.
+    ///
+    /// The side platforms on Fountain of Dreams move, these are their starting heights.
+    /// Use `StageInfo::Fountain` for their actual heights.
+    pub fn platforms(self) -> &'static [Platform] {
+        const fn plat(left: f32, right: f32, height: f32) -> Platform {
+            Platform { left, right, height }
+        }
+
+        static FOUNTAIN: [Platform; 3] = [
+            plat(-49.5, -21.0, 16.125),
+            plat(21.0, 49.5, 16.125),
+            plat(-14.25, 14.25, 42.75),
+        ];
+        static STADIUM: [Platform; 2] = [
+            plat(-55.0, -25.0, 25.0),
+            plat(25.0, 55.0, 25.0),
+        ];
+        static YOSHIS: [Platform; 3] = [
+            plat(-59.5, -28.0, 23.45),
+            plat(28.0, 59.5, 23.45),
+            plat(-15.75, 15.75, 42.0),
+        ];
+        static DREAMLAND: [Platform; 3] = [
+            plat(-61.393, -31.725, 30.2428),
+            plat(31.704, 63.075, 30.2428),
+            plat(-19.018, 19.017, 51.4264),
+        ];
+        static BATTLEFIELD: [Platform; 3] = [
+            plat(-57.6, -20.0, 27.2),
+            plat(20.0, 57.6, 27.2),
+            plat(-18.8, 18.8, 54.4),
+        ];
+
+        match self {
+            Stage::FountainOfDreams => &FOUNTAIN,
+            Stage::PokemonStadium   => &STADIUM,
+            Stage::YoshisStory      => &YOSHIS,
+            Stage::DreamLandN64     => &DREAMLAND,
+            Stage::Battlefield      => &BATTLEFIELD,
+            _ => &[],
+        }
+    }
+
+    /// The surface a grounded position is standing on.
+    /// Always `Stage` on stages without platforms.
+    pub fn surface_at(self, position: Vector) -> WavelandSurface {
+        let platforms = self.platforms();
+        match self.platform_at(position) {
+            Some(i) if platforms.iter().all(|o| o.height < platforms[i].height || std::ptr::eq(o, &platforms[i])) => WavelandSurface::TopPlatform,
+            Some(_) => WavelandSurface::SidePlatform,
+            None => WavelandSurface::Stage,
+        }
+    }
+
+    /// Whether a grounded position is within `distance` of the end of the main stage or a platform.
+    /// Always false for non tournament legal stages.
+    pub fn near_edge(self, position: Vector, distance: f32) -> bool {
+        // fountain platforms move and yoshis slopes near the ledge, so heights are only roughly matched
+        const HEIGHT_LEEWAY: f32 = 10.0;
+
+        let Some([_, ledge]) = self.ledges() else { return false };
+        if (position.x.abs() - ledge.x).abs() <= distance && (position.y - ledge.y).abs() <= HEIGHT_LEEWAY {
+            return true;
+        }
+
+        self.platforms().iter().any(|p| {
+            let at_edge = (position.x - p.left).abs() <= distance || (position.x - p.right).abs() <= distance;
+            at_edge && (position.y - p.height).abs() <= HEIGHT_LEEWAY
+        })
+    }
+
+    /// Platforms while Pokemon Stadium is in a transformation, or `platforms` if None.
+    /// The transformed layouts are not modelled, so every transformation other than `Normal` has no platforms.
+    pub fn platforms_during(self, transformation: Option<StadiumTransformation>) -> &'static [Platform] {
+        match (self, transformation) {
+            (Stage::PokemonStadium, Some(t)) if t != StadiumTransformation::Normal => &[],
+            _ => self.platforms(),
+        }
+    }
+
+    /// Index into `platforms` of the platform a grounded position is standing on.
+    /// None if on the main stage.
+    pub fn platform_at(self, position: Vector) -> Option<usize> {
+        self.platform_at_during(position, None)
+    }
+
+    /// Like `platform_at`, but indexes into `platforms_during(transformation)`.
+    pub fn platform_at_during(self, position: Vector, transformation: Option<StadiumTransformation>) -> Option<usize> {
+        let platforms = self.platforms_during(transformation);
+        let lowest = platforms.iter().map(|p| p.height).fold(f32::INFINITY, f32::min);
+
+        // fountain platforms move, so compare against the nearest platform rather than an exact height
+        if position.y < lowest - 5.0 { return None }
+
+        platforms.iter()
+            .enumerate()
+            .filter(|(_, p)| p.left <= position.x && position.x <= p.right)
+            .min_by(|(_, a), (_, b)| (a.height - position.y).abs().total_cmp(&(b.height - position.y).abs()))
+            .map(|(i, _)| i)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WavelandSurface {
+    Stage,
+    TopPlatform,
+    SidePlatform,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CharacterAttributes {
+    pub weight: u8,
+    pub gravity: f32, // y velocity lost per frame while airborne
+    pub fall_speed: f32, // terminal velocity
+    pub fast_fall_speed: f32,
+    pub air_friction: f32,
+    pub jump_force: f32, // initial y velocity of a full hop
+    pub short_hop_force: f32, // initial y velocity of a short hop
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlastZones {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Platform {
+    pub left: f32,
+    pub right: f32,
+    pub height: f32,
 }
 
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum Character {
     Mario          = 00,
@@ -246,6 +434,119 @@ impl Character {
         }
     }
 
+    /// Compact name for small UIs. Use `Display` for the full name.
+    pub fn short_name(self) -> &'static str {
+        match self {
+            Character::Mario          => "Mario",
+            Character::Fox            => "Fox",
+            Character::CaptainFalcon  => "Falcon",
+            Character::DonkeyKong     => "DK",
+            Character::Kirby          => "Kirby",
+            Character::Bowser         => "Bowser",
+            Character::Link           => "Link",
+            Character::Sheik          => "Sheik",
+            Character::Ness           => "Ness",
+            Character::Peach          => "Peach",
+            Character::Popo           => "Popo",
+            Character::Nana           => "Nana",
+            Character::Pikachu        => "Pika",
+            Character::Samus          => "Samus",
+            Character::Yoshi          => "Yoshi",
+            Character::Jigglypuff     => "Puff",
+            Character::Mewtwo         => "Mewtwo",
+            Character::Luigi          => "Luigi",
+            Character::Marth          => "Marth",
+            Character::Zelda          => "Zelda",
+            Character::YoungLink      => "YLink",
+            Character::DrMario        => "Doc",
+            Character::Falco          => "Falco",
+            Character::Pichu          => "Pichu",
+            Character::MrGameAndWatch => "G&W",
+            Character::Ganondorf      => "Ganon",
+            Character::Roy            => "Roy",
+        }
+    }
+
+    /// Physics attributes from the character data files.
+    pub fn attributes(self) -> CharacterAttributes {
+        const fn attr(
+            weight: u8, gravity: f32, fall_speed: f32, fast_fall_speed: f32, 
+            air_friction: f32, jump_force: f32, short_hop_force: f32,
+        ) -> CharacterAttributes {
+            CharacterAttributes { weight, gravity, fall_speed, fast_fall_speed, air_friction, jump_force, short_hop_force }
+        }
+
+        // indexed by internal character id
+        static ATTRIBUTES: [CharacterAttributes; 27] = [
+            attr(100, 0.095, 1.70, 2.30, 0.010, 2.30, 1.50), // Mario
+            attr( 75, 0.230, 2.80, 3.40, 0.020, 3.68, 2.10), // Fox
+            attr(104, 0.130, 2.90, 3.50, 0.010, 3.10, 1.90), // CaptainFalcon
+            attr(114, 0.100, 2.40, 2.96, 0.020, 2.60, 1.60), // DonkeyKong
+            attr( 70, 0.080, 1.60, 2.00, 0.020, 2.00, 1.40), // Kirby
+            attr(117, 0.130, 1.90, 2.40, 0.010, 2.30, 1.30), // Bowser
+            attr(104, 0.110, 2.13, 3.00, 0.005, 2.50, 1.50), // Link
+            attr( 90, 0.120, 2.13, 3.00, 0.040, 2.80, 2.14), // Sheik
+            attr( 94, 0.090, 1.83, 2.20, 0.030, 2.50, 1.50), // Ness
+            attr( 90, 0.080, 1.50, 2.00, 0.005, 2.20, 1.50), // Peach
+            attr( 88, 0.100, 1.60, 2.00, 0.035, 2.50, 1.50), // Popo
+            attr( 88, 0.100, 1.60, 2.00, 0.035, 2.50, 1.50), // Nana
+            attr( 80, 0.110, 1.90, 2.70, 0.020, 2.60, 1.60), // Pikachu
+            attr(110, 0.066, 1.40, 2.30, 0.010, 2.10, 1.70), // Samus
+            attr(108, 0.093, 1.93, 2.30, 0.013, 2.50, 1.60), // Yoshi
+            attr( 60, 0.064, 1.30, 1.60, 0.050, 1.60, 1.00), // Jigglypuff
+            attr( 85, 0.082, 1.50, 2.30, 0.010, 2.30, 1.40), // Mewtwo
+            attr(100, 0.069, 1.60, 2.00, 0.010, 2.40, 1.40), // Luigi
+            attr( 87, 0.085, 2.20, 2.50, 0.005, 2.40, 1.50), // Marth
+            attr( 90, 0.073, 1.40, 1.96, 0.020, 2.10, 1.50), // Zelda
+            attr( 85, 0.110, 2.13, 2.40, 0.005, 2.62, 1.60), // YoungLink
+            attr(100, 0.095, 1.70, 2.30, 0.010, 2.30, 1.50), // DrMario
+            attr( 80, 0.170, 3.10, 3.50, 0.020, 4.10, 1.90), // Falco
+            attr( 55, 0.110, 1.90, 2.80, 0.020, 2.60, 1.60), // Pichu
+            attr( 60, 0.095, 1.70, 2.30, 0.020, 2.20, 1.60), // MrGameAndWatch
+            attr(109, 0.130, 2.00, 2.60, 0.020, 2.60, 2.00), // Ganondorf
+            attr( 85, 0.114, 2.40, 2.90, 0.010, 2.40, 1.50), // Roy
+        ];
+
+        ATTRIBUTES[self as usize]
+    }
+
+    /// First animation frame the player can act on for jab 1, 2 and 3.
+    /// This is the end of the animation for jabs without an IASA frame. Zero if the character has no such jab.
+    pub fn jab_iasa(self) -> [f32; 3] {
+        // indexed by internal character id
+        static JAB_IASA: [[f32; 3]; 27] = [
+            [17.0, 19.0, 32.0], // Mario
+            [17.0, 20.0,  0.0], // Fox
+            [17.0, 19.0, 34.0], // CaptainFalcon
+            [22.0, 22.0,  0.0], // DonkeyKong
+            [17.0, 17.0,  0.0], // Kirby
+            [23.0, 25.0,  0.0], // Bowser
+            [17.0, 16.0, 30.0], // Link
+            [17.0, 18.0,  0.0], // Sheik
+            [17.0, 18.0, 27.0], // Ness
+            [17.0, 19.0,  0.0], // Peach
+            [17.0, 17.0,  0.0], // Popo
+            [17.0, 17.0,  0.0], // Nana
+            [20.0,  0.0,  0.0], // Pikachu
+            [17.0, 20.0,  0.0], // Samus
+            [17.0, 17.0,  0.0], // Yoshi
+            [17.0, 17.0,  0.0], // Jigglypuff
+            [23.0,  0.0,  0.0], // Mewtwo
+            [17.0, 19.0, 30.0], // Luigi
+            [20.0, 22.0,  0.0], // Marth
+            [22.0,  0.0,  0.0], // Zelda
+            [17.0, 16.0, 30.0], // YoungLink
+            [17.0, 19.0, 32.0], // DrMario
+            [17.0, 20.0,  0.0], // Falco
+            [20.0,  0.0,  0.0], // Pichu
+            [17.0,  0.0,  0.0], // MrGameAndWatch
+            [22.0,  0.0,  0.0], // Ganondorf
+            [20.0, 22.0,  0.0], // Roy
+        ];
+
+        JAB_IASA[self as usize]
+    }
+
     pub fn to_u8_internal(self) -> u8 { self as u8 }
 
     pub fn to_u8_external(self) -> Option<u8> {
@@ -347,6 +648,7 @@ impl Character {
 }
 
 #[derive(Hash, Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CharacterColour {
     Mario         (MarioColour),
     Fox           (FoxColour),
@@ -442,6 +744,11 @@ impl CharacterColour {
         }
     }
 
+    /// Inverse of `from_character_and_colour`.
+    pub fn to_pair(self) -> (Character, u8) {
+        (self.character(), self.costume_idx())
+    }
+
     pub fn character(self) -> Character {
         match self {
             CharacterColour::Mario          (..) => Character::Mario         ,
@@ -521,6 +828,7 @@ pub mod character_colours {
     macro_rules! colour {
         (pub enum $char:ident { $($colour:ident = $n:expr),* $(,)? }) => {
             #[derive(Hash, Copy, Clone, Debug, PartialEq, Eq)]
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
             pub enum $char {
                 $($colour = $n,)*
             }
@@ -570,3 +878,71 @@ pub mod character_colours {
     colour!(pub enum PichuColour          { Neutral = 0, Red    = 1, Blue     = 2, Green     = 3                        });
     colour!(pub enum GanondorfColour      { Neutral = 0, Red    = 1, Blue     = 2, Green     = 3, Lavender = 4          });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn character_names() {
+        for n in 0..27 {
+            let character = Character::from_u8_internal(n).unwrap();
+            assert!(!character.to_string().is_empty(), "{:?}", character);
+            assert!(!character.short_name().is_empty(), "{:?}", character);
+        }
+        assert_eq!(Character::from_u8_internal(27), None);
+
+        assert_eq!(Character::MrGameAndWatch.to_string(), "Mr. Game & Watch");
+        assert_eq!(Character::CaptainFalcon.to_string(), "Captain Falcon");
+        assert_eq!(Character::CaptainFalcon.short_name(), "Falcon");
+    }
+
+    #[test]
+    fn character_attributes() {
+        assert_eq!(Character::Fox.attributes().fall_speed, 2.8);
+        assert_eq!(Character::Fox.attributes().fast_fall_speed, 3.4);
+        assert_eq!(Character::Fox.attributes().weight, 75);
+        assert_eq!(Character::Jigglypuff.attributes().weight, 60);
+        assert_eq!(Character::Bowser.attributes().weight, 117);
+        assert_eq!(Character::Falco.attributes().gravity, 0.17);
+    }
+
+    #[test]
+    fn legal_stage_external_ids() {
+        for (id, stage) in [
+            (2, Stage::FountainOfDreams),
+            (3, Stage::PokemonStadium),
+            (8, Stage::YoshisStory),
+            (28, Stage::DreamLandN64),
+            (31, Stage::Battlefield),
+            (32, Stage::FinalDestination),
+        ] {
+            assert_eq!(Stage::from_external_id(id), Some(stage));
+            assert_eq!(stage.to_u16_external(), id);
+            assert!(stage.is_tournament_legal());
+        }
+
+        assert_eq!(Stage::from_external_id(0), None);
+        assert_eq!(Stage::from_external_id(21), None);
+    }
+
+    #[test]
+    fn character_colour_round_trip() {
+        for n in 0..27 {
+            let character = Character::from_u8_internal(n).unwrap();
+            let mut colours = 0;
+            for idx in 0..=u8::MAX {
+                if let Some(colour) = CharacterColour::from_character_and_colour(character, idx) {
+                    assert_eq!(colour.to_pair(), (character, idx), "{}", colour);
+                    colours += 1;
+                }
+            }
+            assert!(colours >= 4, "{:?}", character);
+        }
+
+        assert_eq!(
+            CharacterColour::from_character_and_colour(Character::Fox, 1),
+            Some(CharacterColour::Fox(FoxColour::Orange)),
+        );
+    }
+}
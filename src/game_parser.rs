@@ -1,26 +1,135 @@
 use crate::*;
 
 pub fn parse(frames: &[Frame]) -> Vec<crate::Action> {
+    parse_with_config(frames, &ParseConfig::default())
+}
+
+pub fn parse_with_config(frames: &[Frame], config: &ParseConfig) -> Vec<crate::Action> {
+    parse_with_skipped(frames, config).0
+}
+
+/// Index of the first frame after the entry animation, when the player can first act.
+pub fn intro_end(frames: &[Frame]) -> usize {
+    use StandardActionState::{Entry, EntryStart, EntryEnd};
+
+    frames.iter()
+        .position(|f| !matches!(f.state, ActionState::Standard(Entry | EntryStart | EntryEnd)))
+        .unwrap_or(frames.len())
+}
+
+/// Frames the parser could not turn into an action, so were skipped.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SkippedFrames {
+    pub frame_start: usize,
+    pub frame_end: usize,
+    pub state: ActionState, // state on frame_start
+}
+
+/// Like `parse_with_config`, but also returns the frames that were not understood.
+pub fn parse_with_skipped(frames: &[Frame], config: &ParseConfig) -> (Vec<crate::Action>, Vec<SkippedFrames>) {
     println!("parse");
-    let mut actions = Vec::new();
+    parse_actions(ActionBuilder::with_config(frames, *config))
+}
+
+/// Parses actions starting at `start_frame`.
+/// Earlier frames are still visible to the parser, so actions that look back are unaffected.
+/// Frame indices in the returned actions are indices into `frames`.
+pub fn parse_from(frames: &[Frame], config: &ParseConfig, start_frame: usize) -> Vec<crate::Action> {
+    parse_actions(ActionBuilder::starting_at(frames, *config, start_frame)).0
+}
+
+/// How the parser produced an action, or skipped frames. See `parse_traced`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceEntry {
+    pub frame_start: usize,
+    pub frame_end: usize,
+    pub broad_states: Vec<BroadState>, // consumed, with consecutive repeats removed
+    pub parsers: Vec<&'static str>, // parse functions dispatched to, outermost first
+    pub action: Option<HighLevelAction>, // None if the frames were skipped
+}
+
+/// Like `parse`, but also records how each action was parsed. For debugging.
+pub fn parse_traced(frames: &[Frame]) -> (Vec<crate::Action>, Vec<TraceEntry>) {
     let mut consumer = ActionBuilder::new(frames);
+    consumer.parsers = Some(Vec::new());
+    let mut trace = Vec::new();
+    let (actions, _) = parse_actions_traced(consumer, Some(&mut trace));
+    (actions, trace)
+}
+
+/// Parses actions within `start..end`.
+/// Parsing begins at the first actionable frame at or after `start`, so an action in progress is skipped.
+/// Actions are cut off at `end`.
+pub fn parse_range(frames: &[Frame], start: usize, end: usize) -> Vec<crate::Action> {
+    parse_range_with_config(frames, &ParseConfig::default(), start, end)
+}
+
+pub fn parse_range_with_config(frames: &[Frame], config: &ParseConfig, start: usize, end: usize) -> Vec<crate::Action> {
+    let end = end.min(frames.len());
+    let frames = &frames[..end];
+    let start = match frames.get(start..).and_then(|f| f.iter().position(|f| f.state.is_actionable())) {
+        Some(i) => start + i,
+        None => return Vec::new(),
+    };
+    parse_actions(ActionBuilder::starting_at(frames, *config, start)).0
+}
+
+fn parse_actions(consumer: ActionBuilder) -> (Vec<crate::Action>, Vec<SkippedFrames>) {
+    parse_actions_traced(consumer, None)
+}
+
+fn parse_actions_traced(
+    mut consumer: ActionBuilder, 
+    mut trace: Option<&mut Vec<TraceEntry>>,
+) -> (Vec<crate::Action>, Vec<SkippedFrames>) {
+    let mut actions = Vec::new();
+    let mut skipped = Vec::new();
+    let mut prev_end = consumer.current_frame();
     while !consumer.finished() {
         if let Err(_) = consumer.start_action() { break }
-        match Action::parse_next(&mut consumer) {
-            Ok(action) => actions.push(action),
+        let frame_start = consumer.current_frame();
+        let state = consumer.peek();
+        let result = Action::parse_next(&mut consumer);
+
+        if let Some(ref mut trace) = trace {
+            let frame_end = consumer.current_frame().max(frame_start + 1).min(consumer.all_frames().len());
+            let mut broad_states: Vec<BroadState> = consumer.all_frames()[frame_start..frame_end].iter()
+                .map(|f| f.state.broad_state())
+                .collect();
+            broad_states.dedup();
+            trace.push(TraceEntry {
+                frame_start,
+                frame_end,
+                broad_states,
+                parsers: consumer.parsers.as_mut().map(std::mem::take).unwrap_or_default(),
+                action: result.as_ref().ok().map(|a| a.action_taken),
+            });
+        }
+
+        match result {
+            Ok(mut action) => {
+                action.frames_since_prev = action.frame_start.saturating_sub(prev_end);
+                prev_end = action.frame_end;
+                actions.push(action);
+            }
             Err(ParseError::EOF) => {
                 //println!("EOF")
             },
             Err(ParseError::Unknown) => {
-                //println!("unknown");
+                // always make progress, otherwise an unknown state would loop forever
+                if consumer.current_frame() == frame_start { consumer.next(); }
+                if let Some(state) = state {
+                    skipped.push(SkippedFrames { frame_start, frame_end: consumer.current_frame(), state });
+                }
             }
         }
     }
 
-    actions
+    (actions, skipped)
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum JumpType {
     Full,
     Short,
@@ -33,9 +142,65 @@ enum CourtesyReturn {
     SkipMax,
 }
 
+/// Courtesy timeouts, in frames.
+///
+/// If a state is held for less than its timeout, it is treated as leeway 
+/// and merged into the following action.
+/// Otherwise, it is a deliberate wait and becomes its own action.
+///
+/// Lower timeouts split waits from the following action more eagerly.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ParseConfig {
+    /// Airborne frames after a jump or in the air before `AirWait`.
+    pub air_timeout: usize,
+    /// Frames after a double jump before it is a plain `AirJump`.
+    pub airjump_timeout: usize,
+    /// Standing frames before `GroundWait`.
+    pub ground_timeout: usize,
+    /// Walking frames before `WalkLeft`/`WalkRight`.
+    pub walk_timeout: usize,
+    /// Shielding frames before `Shield`.
+    pub shield_timeout: usize,
+    /// Airborne frames after hitstun that are still counted as part of the hitstun.
+    pub hitstun_timeout: usize,
+    /// Frames on ledge before `LedgeWait`.
+    pub ledge_timeout: usize,
+    /// Dashing frames before `DashLeft`/`DashRight`.
+    pub dash_timeout: usize,
+    /// Crouching frames before `Crouch`.
+    pub crouch_timeout: usize,
+    /// Stage the frames were played on, used to find which surface wavelands land on.
+    /// If None, every waveland lands on the stage.
+    pub stage: Option<Stage>,
+    /// Skip the entry animation before "Go!", see `intro_end`.
+    pub skip_intro: bool,
+    /// Emit `Inactionable` actions for generic inactionable states and special landings
+    /// instead of leaving them as gaps between actions.
+    pub emit_inactionable: bool,
+}
+
+impl Default for ParseConfig {
+    fn default() -> Self {
+        ParseConfig {
+            air_timeout: 10,
+            airjump_timeout: 10,
+            ground_timeout: 5,
+            walk_timeout: 5,
+            shield_timeout: 5,
+            hitstun_timeout: 5,
+            ledge_timeout: 15,
+            dash_timeout: 3,
+            crouch_timeout: 5,
+            stage: None,
+            skip_intro: false,
+            emit_inactionable: false,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 struct Courtesy {
-    pub timeout: usize,
+    pub timeout: fn(&ParseConfig) -> usize,
     pub state: StandardBroadState,
 }
 
@@ -47,45 +212,47 @@ pub enum ParseError {
 
 impl Action {
     const AIR_COURTESY: Courtesy = Courtesy {
-        timeout: 10,
+        timeout: |c| c.air_timeout,
         state: StandardBroadState::Air,
     };
     const AIRJUMP_COURTESY: Courtesy = Courtesy {
-        timeout: 10,
+        timeout: |c| c.airjump_timeout,
         state: StandardBroadState::AirJump,
     };
     const GROUND_COURTESY: Courtesy = Courtesy {
-        timeout: 5,
+        timeout: |c| c.ground_timeout,
         state: StandardBroadState::Ground,
     };
     const WALK_COURTESY: Courtesy = Courtesy {
-        timeout: 5,
+        timeout: |c| c.walk_timeout,
         state: StandardBroadState::Walk,
     };
     const SHIELD_COURTESY: Courtesy = Courtesy {
-        timeout: 5,
+        timeout: |c| c.shield_timeout,
         state: StandardBroadState::Shield,
     };
     const HITSTUN_COURTESY: Courtesy = Courtesy {
-        timeout: 5,
+        timeout: |c| c.hitstun_timeout,
         state: StandardBroadState::Air,
     };
     const LEDGE_COURTESY: Courtesy = Courtesy {
-        timeout: 15,
+        timeout: |c| c.ledge_timeout,
         state: StandardBroadState::Ledge,
     };
-    const DASH_COURTESY: Courtesy = Courtesy {
-        timeout: 3,
-        state: StandardBroadState::DashRun,
-    };
     const CROUCH_COURTESY: Courtesy = Courtesy {
-        timeout: 5,
+        timeout: |c| c.crouch_timeout,
         state: StandardBroadState::Crouch,
     };
 
+    // max distance from an edge on the last landing frame of an edge cancel
+    const EDGE_CANCEL_DISTANCE: f32 = 6.0;
+
     // returns None if action is unknown or eof
     pub fn parse_next(consumer: &mut ActionBuilder) -> Result<Self, ParseError> {
         let state = consumer.peek().ok_or(ParseError::EOF)?.broad_state();
+        if let Some(ref mut parsers) = consumer.parsers {
+            parsers.push(Action::parser_name(state));
+        }
         match state {
             BroadState::Standard(st) => Self::parse_next_standard(consumer, st),
             BroadState::Special(st) => Self::parse_next_special(consumer, st),
@@ -132,33 +299,86 @@ impl Action {
             Attack => Action::parse_attack(consumer),
             Air => Action::parse_courtesy(consumer, Action::AIR_COURTESY, HighLevelAction::AirWait),
             Airdodge => Action::parse_airdodge(consumer),
-            SpecialLanding => {
-                consumer.skip_broad_state(SpecialLanding);
-                Err(ParseError::Unknown)
-            }
+            SpecialLanding => Action::parse_inactionable(consumer, SpecialLanding),
+            SpecialFall => Action::parse_special_fall(consumer),
             Ground => Action::parse_courtesy(consumer, Action::GROUND_COURTESY, HighLevelAction::GroundWait),
             Walk => Action::parse_walk(consumer),
             DashRun => Action::parse_dash(consumer),
-            Shield => Action::parse_courtesy(consumer, Action::SHIELD_COURTESY, HighLevelAction::Shield),
+            Shield => Action::parse_shield(consumer),
             Ledge => Action::parse_ledge(consumer),
             LedgeAction => Action::parse_ledge_action(consumer), // probably never happens
             Hitstun => Action::parse_hitstun(consumer),
-            GenericInactionable => {
-                consumer.skip_broad_state(GenericInactionable);
-                Err(ParseError::Unknown)
-            }
+            GenericInactionable => Action::parse_inactionable(consumer, GenericInactionable),
             JumpSquat => Action::parse_jump_squat(consumer),
             AirJump => Action::parse_air_jump(consumer),
             Crouch => Action::parse_courtesy(consumer, Action::CROUCH_COURTESY, HighLevelAction::Crouch),
-            Grab => Action::parse_simple_action(consumer, Grab.into(), HighLevelAction::Grab),
+            Grab => Action::parse_grab(consumer),
+            Throw => Action::parse_throw(consumer),
             Roll => Action::parse_roll(consumer),
             Spotdodge => {
+                Action::check_out_of_shield(consumer);
                 Action::parse_simple_action(consumer, Spotdodge.into(), HighLevelAction::Spotdodge)
             }
+            Knockdown => Action::parse_knockdown(consumer),
+            Turn => Action::parse_turn(consumer),
         }
     }
 
+    /// Name of the function `parse_next` dispatches to, for tracing.
+    fn parser_name(state: BroadState) -> &'static str {
+        use StandardBroadState::*;
+
+        match state {
+            BroadState::Special(_) => "parse_special",
+            BroadState::Standard(st) => match st {
+                Attack => "parse_attack",
+                Air | Ground | Crouch => "parse_courtesy",
+                Airdodge => "parse_airdodge",
+                SpecialLanding | GenericInactionable => "parse_inactionable",
+                SpecialFall => "parse_special_fall",
+                Walk => "parse_walk",
+                DashRun => "parse_dash",
+                Shield => "parse_shield",
+                Ledge => "parse_ledge",
+                LedgeAction => "parse_ledge_action",
+                Hitstun => "parse_hitstun",
+                JumpSquat => "parse_jump_squat",
+                AirJump => "parse_air_jump",
+                Grab => "parse_grab",
+                Throw => "parse_throw",
+                Roll => "parse_roll",
+                Spotdodge => "parse_simple_action",
+                Knockdown => "parse_knockdown",
+                Turn => "parse_turn",
+            }
+        }
+    }
+
+    /// Call before consuming the first frame of the option.
+    /// Sets whether it was performed directly out of shield, 
+    /// and how long after shieldstun ended.
+    fn check_out_of_shield(consumer: &mut ActionBuilder) {
+        // GuardOff is not in the shield broad state, but still counts as out of shield
+        let is_shield = |f: &Frame| f.state.broad_state() == BroadState::Standard(StandardBroadState::Shield)
+            || f.state == ActionState::Standard(StandardActionState::GuardOff);
+
+        let cur = consumer.current_frame();
+        let frames = &consumer.all_frames()[..cur];
+        if !frames.last().is_some_and(is_shield) { return }
+
+        let shield_start = frames.iter().rposition(|f| !is_shield(f)).map_or(0, |i| i + 1);
+        let oos_frames = frames[shield_start..].iter()
+            .rposition(|f| f.state == ActionState::Standard(StandardActionState::GuardSetOff))
+            .map(|i| (frames.len() - (shield_start + i + 1)).min(u8::MAX as usize) as u8);
+
+        consumer.set_out_of_shield(true, oos_frames);
+
+        let shield_frames = (frames.len() - shield_start).min(u16::MAX as usize) as u16;
+        consumer.set_shield_frames(Some(shield_frames));
+    }
+
     fn parse_roll(consumer: &mut ActionBuilder) -> Result<Action, ParseError> {
+        Action::check_out_of_shield(consumer);
         let roll_state = consumer.next().ok_or(ParseError::EOF)?;
         let hla = match roll_state {
             ActionState::Standard(StandardActionState::EscapeF) => HighLevelAction::RollForward,
@@ -178,17 +398,244 @@ impl Action {
         Ok(consumer.finish_action(hla))
     }
 
+    fn parse_grab(consumer: &mut ActionBuilder) -> Result<Action, ParseError> {
+        use StandardActionState::*;
+
+        Action::check_out_of_shield(consumer);
+
+        let mut pulled = false;
+        let mut released = false;
+        let mut pummels = 0u8;
+        let mut grab_frames = 0u16;
+        let mut prev_state = None;
+        while let Some(st) = consumer.peek() {
+            if st.broad_state() != StandardBroadState::Grab.into() { break }
+
+            match st {
+                ActionState::Standard(CatchPull | CatchDashPull | CatchWait) => pulled = true,
+                ActionState::Standard(CatchAttack) if prev_state != Some(st) => pummels = pummels.saturating_add(1),
+                ActionState::Standard(CatchCut) => released = true,
+                _ => (),
+            }
+
+            if pulled && !released { grab_frames = grab_frames.saturating_add(1) }
+
+            prev_state = Some(st);
+            consumer.next();
+        }
+
+        consumer.set_pummels(pummels);
+
+        if !pulled {
+            return Ok(consumer.finish_action(HighLevelAction::GrabWhiff));
+        }
+
+        consumer.set_grab_frames(Some(grab_frames));
+
+        if released {
+            return Ok(consumer.finish_action(HighLevelAction::GrabRelease));
+        }
+
+        match consumer.peek() {
+            Some(ActionState::Standard(st)) if st.throw_direction().is_some() => Action::parse_throw(consumer),
+            _ => Ok(consumer.finish_action(HighLevelAction::Grab)),
+        }
+    }
+
+    fn parse_throw(consumer: &mut ActionBuilder) -> Result<Action, ParseError> {
+        let throw_state = consumer.peek().ok_or(ParseError::EOF)?;
+        let dir = throw_state.assert_standard().throw_direction()
+            .ok_or(ParseError::Unknown)?;
+
+        consumer.skip_broad_state(StandardBroadState::Throw);
+        Ok(consumer.finish_action(HighLevelAction::Throw(dir)))
+    }
+
+    fn parse_shield(consumer: &mut ActionBuilder) -> Result<Action, ParseError> {
+        fn is_pass(st: ActionState) -> bool {
+            st == ActionState::Standard(StandardActionState::Pass)
+        }
+
+        let c_ret = Action::skip_courtesy(consumer, Action::SHIELD_COURTESY);
+        if c_ret == CourtesyReturn::SkipMax {
+            consumer.skip_broad_state(StandardBroadState::Shield);
+        }
+
+        // only a shield held on a platform can be dropped through.
+        // never a shield drop without a known stage, or on stages without platforms.
+        let passing = consumer.peek().map(is_pass) == Some(true);
+        let on_platform = match (consumer.config().stage, consumer.prev_frame()) {
+            (Some(stage), Some(shield_f)) => stage.platform_at(shield_f.position).is_some(),
+            _ => false,
+        };
+
+        if passing && on_platform {
+            consumer.skip_while(is_pass);
+            Ok(consumer.finish_action(HighLevelAction::ShieldDrop))
+        } else if passing || c_ret == CourtesyReturn::SkipMax {
+            Ok(consumer.finish_action(HighLevelAction::Shield))
+        } else {
+            Action::parse_next(consumer)
+        }
+    }
+
+    fn parse_turn(consumer: &mut ActionBuilder) -> Result<Action, ParseError> {
+        use StandardBroadState::*;
+
+        // turning while walking or standing is not a pivot
+        let from_dash = consumer.prev_frame()
+            .map(|f| f.state.broad_state()) == Some(DashRun.into());
+
+        consumer.skip_broad_state(Turn);
+        let post_turn_state = consumer.peek().ok_or(ParseError::EOF)?.broad_state();
+
+        // standing and walking turnarounds fold into the following wait, walk, or action
+        if !from_dash || post_turn_state == DashRun.into() {
+            return Action::parse_courtesy(consumer, Action::GROUND_COURTESY, HighLevelAction::GroundWait);
+        }
+
+        if post_turn_state == Grab.into() {
+            let grab = Action::parse_grab(consumer)?;
+            let hla = match grab.action_taken {
+                // whiffed pivot grabs stay as GrabWhiff
+                HighLevelAction::Grab => HighLevelAction::PivotGrab,
+                hla => hla,
+            };
+            return Ok(Action { action_taken: hla, ..grab });
+        }
+
+        // an empty pivot waits out the whole courtesy, otherwise it's a pivot into another action
+        if post_turn_state == Ground.into()
+            && Action::skip_courtesy(consumer, Action::GROUND_COURTESY) == CourtesyReturn::SkipMax
+        {
+            consumer.skip_broad_state(Ground);
+            Ok(consumer.finish_action(HighLevelAction::EmptyPivot))
+        } else {
+            Ok(consumer.finish_action(HighLevelAction::Pivot))
+        }
+    }
+
+    fn parse_knockdown(consumer: &mut ActionBuilder) -> Result<Action, ParseError> {
+        use StandardActionState::*;
+
+        fn roll_direction(facing: Direction, forward: bool) -> Direction {
+            if forward { facing } else { facing.flip() }
+        }
+
+        let knockdown_f = consumer.peek_frame().ok_or(ParseError::EOF)?;
+        let hla = match knockdown_f.state {
+            ActionState::Standard(Passive) => HighLevelAction::TechInPlace,
+            ActionState::Standard(PassiveStandF) => HighLevelAction::TechRoll(roll_direction(knockdown_f.direction, true)),
+            ActionState::Standard(PassiveStandB) => HighLevelAction::TechRoll(roll_direction(knockdown_f.direction, false)),
+            ActionState::Standard(PassiveWall) => HighLevelAction::TechWall,
+            ActionState::Standard(PassiveWallJump) => HighLevelAction::TechWallJump,
+            ActionState::Standard(PassiveCeil) => HighLevelAction::TechCeiling,
+            _ => {
+                // missed tech - lying down until a getup option is chosen
+                consumer.skip_while(|st| matches!(st, ActionState::Standard(
+                    DownBoundU | DownWaitU | DownDamageU | DownSpotU
+                    | DownBoundD | DownWaitD | DownDamageD | DownSpotD
+                )));
+
+                match consumer.peek_frame() {
+                    Some(getup_f) => match getup_f.state {
+                        ActionState::Standard(DownStandU | DownStandD) => HighLevelAction::MissedTechGetUp,
+                        ActionState::Standard(DownAttackU | DownAttackD) => HighLevelAction::MissedTechAttack,
+                        ActionState::Standard(DownFowardU | DownFowardD) => HighLevelAction::MissedTechRoll(roll_direction(getup_f.direction, true)),
+                        ActionState::Standard(DownBackU | DownBackD) => HighLevelAction::MissedTechRoll(roll_direction(getup_f.direction, false)),
+                        _ => HighLevelAction::MissedTech,
+                    },
+                    None => HighLevelAction::MissedTech,
+                }
+            }
+        };
+
+        consumer.skip_broad_state(StandardBroadState::Knockdown);
+        Ok(consumer.finish_action(hla))
+    }
+
+    fn parse_inactionable(consumer: &mut ActionBuilder, state: StandardBroadState) -> Result<Action, ParseError> {
+        let inactionable_state = consumer.peek();
+        consumer.skip_broad_state(state);
+
+        if !consumer.config().emit_inactionable { return Err(ParseError::Unknown) }
+
+        consumer.set_inactionable_state(inactionable_state);
+        Ok(consumer.finish_action(HighLevelAction::Inactionable))
+    }
+
+    fn parse_special_fall(consumer: &mut ActionBuilder) -> Result<Action, ParseError> {
+        consumer.skip_broad_state(StandardBroadState::SpecialFall);
+
+        // landing lag is part of the fall, ledge grabs are left for the next action
+        if consumer.peek().map(|st| st.broad_state()) == Some(StandardBroadState::SpecialLanding.into()) {
+            consumer.skip_broad_state(StandardBroadState::SpecialLanding);
+        }
+
+        Ok(consumer.finish_action(HighLevelAction::SpecialFall))
+    }
+
     fn parse_dash(consumer: &mut ActionBuilder) -> Result<Action, ParseError> {
-        let dash_frame = consumer.next_frame().unwrap();
-        let dash_hla = match dash_frame.direction {
+        use StandardBroadState::DashRun;
+
+        // a dash back carries momentum from the previous dash for its first frames
+        const MOMENTUM_FRAMES: usize = 4;
+        const MOONWALK_FRAMES: usize = 3;
+        const EPSILON: f32 = 0.1;
+
+        let dash_dir = consumer.peek_frame().unwrap().direction;
+        let is_dash = |f: &Frame| f.state == ActionState::Standard(StandardActionState::Dash);
+        // dashing back stays in the dash state, but turns the player around
+        let is_dash_back = |f: &Frame| is_dash(f) && f.direction != dash_dir;
+        let is_same_dash = |f: &Frame| f.state.broad_state() == DashRun.into() && !is_dash_back(f);
+
+        // moving against the facing direction while dashing
+        let mut reversed = 0;
+        let is_moonwalk = consumer.remaining_frames().iter()
+            .take_while(|f| is_dash(f) && !is_dash_back(f))
+            .skip(MOMENTUM_FRAMES)
+            .any(|f| {
+                reversed = if f.velocity.x * dash_dir.sign() < -EPSILON { reversed + 1 } else { 0 };
+                reversed >= MOONWALK_FRAMES
+            });
+
+        if is_moonwalk {
+            while consumer.peek_frame().is_some_and(|f| is_dash(f) && !is_dash_back(f)) {
+                consumer.next_frame();
+            }
+            return Ok(consumer.finish_action(HighLevelAction::Moonwalk(dash_dir.flip())));
+        }
+
+        consumer.next_frame();
+        let dash_hla = match dash_dir {
             Direction::Left => HighLevelAction::DashLeft,
             Direction::Right => HighLevelAction::DashRight,
         };
 
-        Action::parse_courtesy(consumer, Action::DASH_COURTESY, dash_hla)
+        // courtesy frames, but a dash back ends the dash so dash dances are parsed per dash
+        let timeout = consumer.config().dash_timeout;
+        let mut skipped = 0;
+        while skipped < timeout && consumer.peek_frame().is_some_and(is_same_dash) {
+            consumer.next_frame();
+            skipped += 1;
+        }
+
+        if skipped == timeout || consumer.peek_frame().is_some_and(is_dash_back) {
+            while consumer.peek_frame().is_some_and(is_same_dash) {
+                consumer.next_frame();
+            }
+            Ok(consumer.finish_action(dash_hla))
+        } else {
+            Action::parse_next(consumer)
+        }
     }
 
     fn parse_attack(consumer: &mut ActionBuilder) -> Result<Action, ParseError> {
+        use StandardActionState::*;
+        if let Some(ActionState::Standard(Attack11 | Attack12 | Attack13)) = consumer.peek() {
+            return Action::parse_jab(consumer);
+        }
+
         let attack_type = Action::parse_attack_to_end(consumer)?;
         let hla = match attack_type {
             AttackType::AirAttack(at) => HighLevelAction::Aerial(at),
@@ -198,6 +645,57 @@ impl Action {
         Ok(consumer.finish_action(hla))
     }
 
+    /// Jab 1, 2 and 3, not including rapid jabs, which are parsed as their own attack.
+    fn parse_jab(consumer: &mut ActionBuilder) -> Result<Action, ParseError> {
+        use StandardActionState::*;
+        use StandardBroadState as SBS;
+
+        let mut jab_hits = 0u8;
+        let mut prev: Option<&Frame> = None;
+        while let Some(frame) = consumer.peek_frame() {
+            if !matches!(frame.state, ActionState::Standard(Attack11 | Attack12 | Attack13)) { break }
+
+            // repeated jab 1s stay in the same state, but restart the animation
+            let new_hit = match prev {
+                Some(p) => p.state != frame.state || frame.anim_frame < p.anim_frame,
+                None => true,
+            };
+            if new_hit { jab_hits = jab_hits.saturating_add(1) }
+
+            prev = Some(frame);
+            consumer.next();
+        }
+        consumer.set_jab_hits(jab_hits);
+
+        // acting after the jab's iasa frame is not a cancel
+        let interrupted = prev.is_some_and(|last| {
+            let jab = match last.state {
+                ActionState::Standard(Attack11) => 0,
+                ActionState::Standard(Attack12) => 1,
+                _ => 2,
+            };
+            last.anim_frame < last.character.jab_iasa()[jab]
+        });
+
+        // a finished jab returns to wait, or continues into rapid jabs
+        let cancelled = interrupted && match consumer.peek() {
+            Some(ActionState::Standard(Attack100Start | Attack100Loop | Attack100End)) => false,
+            Some(st) => matches!(st.broad_state(),
+                BroadState::Standard(
+                    SBS::Attack | SBS::Grab | SBS::Crouch | SBS::DashRun | SBS::Walk | SBS::Shield 
+                    | SBS::JumpSquat | SBS::Turn | SBS::Roll | SBS::Spotdodge
+                ) | BroadState::Special(_)
+            ),
+            None => false,
+        };
+
+        if cancelled {
+            Ok(consumer.finish_action(HighLevelAction::JabCancel))
+        } else {
+            Ok(consumer.finish_action(HighLevelAction::GroundAttack(GroundAttack::Jab)))
+        }
+    }
+
     fn parse_ledge(consumer: &mut ActionBuilder) -> Result<Action, ParseError> {
         use StandardBroadState::*;
 
@@ -253,14 +751,15 @@ impl Action {
                                     let airdodge_action = Action::parse_airdodge(consumer)?;
 
                                     use HighLevelAction::*;
-                                    let new_hla = match airdodge_action.action_taken {
-                                        WavelandLeft | WavelandDown | WavelandRight => LedgeDash,
-                                        hla => hla,
+                                    let (new_hla, galint) = match airdodge_action.action_taken {
+                                        WavelandLeft | WavelandDown | WavelandRight => (LedgeDash, Action::ledgedash_galint(consumer)),
+                                        hla => (hla, None),
                                     };
 
                                     // TODO wtf???
                                     Ok(Action {
                                         action_taken: new_hla,
+                                        galint,
                                         ..airdodge_action
                                     })
                                 }
@@ -279,6 +778,8 @@ impl Action {
                                 }
                                 SpecialLanding => {
                                     consumer.skip_broad_state(SpecialLanding);
+                                    let galint = Action::ledgedash_galint(consumer);
+                                    consumer.set_galint(galint);
                                     Ok(consumer.finish_action(HighLevelAction::LedgeDash))
                                 }
                                 Hitstun => Action::parse_hitstun(consumer),
@@ -305,6 +806,25 @@ impl Action {
         }
     }
 
+    /// Call once the ledgedash is actionable, after the landing lag.
+    /// None if hit during the ledgedash.
+    fn ledgedash_galint(consumer: &ActionBuilder) -> Option<i8> {
+        let is_intangible = |f: &&Frame| f.hurtbox_state == HurtboxState::Intangible;
+        let action_frames = consumer.action_frames();
+
+        if action_frames.iter().any(|f| f.state.broad_state() == StandardBroadState::Hitstun.into()) {
+            return None;
+        }
+
+        let remaining = consumer.remaining_frames().iter().take_while(is_intangible).count();
+        if remaining > 0 {
+            Some(remaining.min(i8::MAX as usize) as i8)
+        } else {
+            let vulnerable = action_frames.iter().rev().take_while(|f| !is_intangible(f)).count();
+            Some(-(vulnerable.min(i8::MAX as usize) as i8))
+        }
+    }
+
     fn parse_ledge_action(consumer: &mut ActionBuilder) -> Result<Action, ParseError> {
         let ledge_action_state = consumer.peek().ok_or(ParseError::EOF)?;
         let ledge_action = ledge_action_state.assert_standard().ledge_action()
@@ -322,6 +842,7 @@ impl Action {
 
     fn parse_hitstun(consumer: &mut ActionBuilder) -> Result<Action, ParseError> {
         let Courtesy { timeout, state } = Action::HITSTUN_COURTESY; // TODO: necessary?
+        let timeout = timeout(consumer.config());
         loop {
             consumer.skip_broad_state(StandardBroadState::Hitstun);
             if consumer.peek_n(timeout).any(|st| st.broad_state() != BroadState::Standard(state)) {
@@ -332,9 +853,47 @@ impl Action {
             }
         }
 
+        let (di_angle, sdi_inputs) = Action::hitstun_di(consumer.action_frames());
+        consumer.set_di(di_angle, sdi_inputs);
+
         Ok(consumer.finish_action(HighLevelAction::Hitstun))
     }
 
+    /// DI is read on the last frame of hitlag. 
+    /// SDI is counted on the frames before it, as the final frame is ASDI. 
+    /// Holding the stick, such as holding down to crouch cancel or ASDI down, is not SDI.
+    fn hitstun_di(frames: &[Frame]) -> (Option<f32>, u8) {
+        const SDI_THRESHOLD: f32 = 0.7;
+
+        let hitlag_start = match frames.iter().position(|f| f.hitlag_frames > 0.0) {
+            Some(i) => i,
+            None => return (None, 0),
+        };
+        let hitlag_len = frames[hitlag_start..].iter().take_while(|f| f.hitlag_frames > 0.0).count();
+        let hitlag = &frames[hitlag_start..hitlag_start + hitlag_len];
+
+        let di = Vector::from(hitlag[hitlag_len - 1].left_stick_coords);
+        let di_angle = if di.x.abs() < STICK_DEADZONE && di.y.abs() < STICK_DEADZONE {
+            None
+        } else {
+            Some(di.angle_degrees())
+        };
+
+        let sdi_region = |f: &Frame| Vector::from(f.left_stick_coords).magnitude() >= SDI_THRESHOLD;
+
+        let mut sdi_inputs = 0u8;
+        let mut was_in_region = hitlag_start.checked_sub(1)
+            .map(|i| sdi_region(&frames[i]))
+            .unwrap_or(false);
+        for f in &hitlag[..hitlag_len - 1] {
+            let in_region = sdi_region(f);
+            if in_region && !was_in_region { sdi_inputs = sdi_inputs.saturating_add(1); }
+            was_in_region = in_region;
+        }
+
+        (di_angle, sdi_inputs)
+    }
+
     fn parse_courtesy(
         consumer: &mut ActionBuilder,
         courtesy: Courtesy,
@@ -366,6 +925,7 @@ impl Action {
     }
 
     fn parse_jump_squat(consumer: &mut ActionBuilder) -> Result<Action, ParseError> {
+        Action::check_out_of_shield(consumer);
         let jump_type = Action::parse_jump_type(consumer)?;
         let hla = match jump_type {
             JumpType::Full => HighLevelAction::Fullhop,
@@ -387,8 +947,10 @@ impl Action {
             use StandardBroadState::*;
             match standard_state {
                 Attack => {
+                    let reverse_aerial_rush = Action::is_reverse_aerial_rush(consumer);
                     let attack_type = Action::parse_attack_to_end(consumer)?;
                     let high_level_action = match attack_type {
+                        AttackType::AirAttack(at) if reverse_aerial_rush => HighLevelAction::ReverseAerialRush(at),
                         AttackType::AirAttack(at) => match jump_type {
                             JumpType::Full => HighLevelAction::FullhopAerial(at),
                             JumpType::Short => HighLevelAction::ShorthopAerial(at),
@@ -415,12 +977,27 @@ impl Action {
                         ..airdodge_action
                     })
                 }
-                Grab => Action::parse_simple_action(consumer, BroadState::Standard(Grab), HighLevelAction::Grab),
+                Grab => Action::parse_grab(consumer),
                 _ => Ok(consumer.finish_action(hla)),
             }
         }
     }
 
+    /// Whether the jump came out of a run turnaround and the attack starting on the next frame
+    /// faces away from the momentum carried through the turn.
+    fn is_reverse_aerial_rush(consumer: &ActionBuilder) -> bool {
+        use StandardActionState::*;
+
+        let action_frames = consumer.action_frames();
+        let (Some(jump_squat_f), Some(attack_f)) = (action_frames.first(), consumer.peek_frame()) else { return false };
+        let jump_squat_idx = consumer.current_frame() - action_frames.len();
+        let Some(turn_f) = jump_squat_idx.checked_sub(1).map(|i| &consumer.all_frames()[i]) else { return false };
+
+        if !matches!(turn_f.state, ActionState::Standard(TurnRun | RunBrake)) { return false }
+
+        jump_squat_f.velocity.x != 0.0 && attack_f.velocity.x * attack_f.direction.sign() < 0.0
+    }
+
     fn parse_jumping_special(consumer: &mut ActionBuilder, state: SpecialBroadState, jump_type: JumpType) -> Result<Action, ParseError> {
         use SpecialBroadState::*;
 
@@ -458,6 +1035,13 @@ impl Action {
 
         const EPSILON: f32 = 0.1;
 
+        let stick = Vector::from(consumer.peek_frame().ok_or(ParseError::EOF)?.left_stick_coords);
+        let airdodge_angle = if stick.x.abs() < STICK_DEADZONE && stick.y.abs() < STICK_DEADZONE {
+            None
+        } else {
+            Some(stick.angle_degrees())
+        };
+
         consumer.skip_broad_state(StandardBroadState::Airdodge);
 
         let post_airdodge_state = consumer.peek().ok_or(ParseError::EOF)?;
@@ -470,9 +1054,21 @@ impl Action {
                     x if x > EPSILON => HighLevelAction::WavelandRight,
                     _ => HighLevelAction::WavelandDown,
                 };
+                let surface = match consumer.config().stage {
+                    Some(stage) => stage.surface_at(frame.position),
+                    None => WavelandSurface::Stage,
+                };
+                consumer.set_waveland_surface(Some(surface));
+                consumer.set_wavedash_angle(airdodge_angle);
                 consumer.skip_broad_state(StandardBroadState::SpecialLanding);
                 Ok(consumer.finish_action(high_level_action))
             }
+            BroadState::Standard(StandardBroadState::SpecialFall) => {
+                // landing after the airdodge has ended is not a waveland
+                consumer.skip_broad_state(StandardBroadState::SpecialFall);
+                consumer.skip_broad_state(StandardBroadState::SpecialLanding);
+                Ok(consumer.finish_action(HighLevelAction::Airdodge))
+            }
             _ => Ok(consumer.finish_action(HighLevelAction::Airdodge)),
         }
     }
@@ -515,36 +1111,59 @@ impl Action {
         let attack_type = attack.attack_type().expect("expected attack state");
         consumer.skip_broad_state(StandardBroadState::Attack);
 
+        // landing lag is not consumed, only inspected for the l-cancel
+        if let AttackType::AirAttack(_) = attack_type {
+            use StandardActionState::*;
+            if let Some(landing_f) = consumer.peek_frame() {
+                if let ActionState::Standard(LandingAirN | LandingAirF | LandingAirB | LandingAirHi | LandingAirLw) = landing_f.state {
+                    let l_cancel = landing_f.l_cancel;
+                    consumer.set_l_cancelled(l_cancel);
+                }
+            }
+
+            // sliding off an edge during landing lag is the only way to be airborne again without being hit
+            let remaining = consumer.remaining_frames();
+            let lag = remaining.iter()
+                .take_while(|f| matches!(f.state, ActionState::Standard(LandingAirN | LandingAirF | LandingAirB | LandingAirHi | LandingAirLw)))
+                .count();
+            if let (Some(last_landing_f), Some(after_f), Some(stage)) = (lag.checked_sub(1).map(|i| &remaining[i]), remaining.get(lag), consumer.config().stage) {
+                let edge_cancelled = after_f.is_airborne
+                    && after_f.state.broad_state() == StandardBroadState::Air.into()
+                    && stage.near_edge(last_landing_f.position, Action::EDGE_CANCEL_DISTANCE);
+                consumer.set_edge_cancelled(edge_cancelled);
+            }
+        }
+
         Ok(attack_type)
     }
 
     /// It is important to allow a little leeway between states.
     /// For instance, a wavedash, if not frame perfect, will contain some airborne frames.
     fn skip_courtesy(consumer: &mut ActionBuilder, c: Courtesy) -> CourtesyReturn {
+        let timeout = (c.timeout)(consumer.config());
         let skipped =
-            consumer.skip_while_at_most(|new_st| new_st.broad_state() == BroadState::Standard(c.state), c.timeout);
+            consumer.skip_while_at_most(|new_st| new_st.broad_state() == BroadState::Standard(c.state), timeout);
         match skipped {
-            n if n == c.timeout => CourtesyReturn::SkipMax,
+            n if n == timeout => CourtesyReturn::SkipMax,
             0 => CourtesyReturn::NoSkip,
             _ => CourtesyReturn::SkipSome,
         }
     }
 
     fn parse_jump_type(consumer: &mut ActionBuilder) -> Result<JumpType, ParseError> {
-        // TODO: !!!!
-        static JUMP_VELOCITIES: [f32; 26] = [0.0; 26];
-
-        let mut last_squat_f = consumer.next_frame().ok_or(ParseError::EOF)?;
+        consumer.next_frame().ok_or(ParseError::EOF)?;
         while consumer.peek().ok_or(ParseError::EOF)?.broad_state() == BroadState::Standard(StandardBroadState::JumpSquat) {
-            last_squat_f = consumer.next_frame().unwrap();
+            consumer.next_frame();
         }
 
-        let character = last_squat_f.character;
-        let y_vel = last_squat_f.velocity.y;
+        // jump squat frames have no y velocity, so we check the first airborne frame
+        let takeoff_f = consumer.peek_frame().ok_or(ParseError::EOF)?;
+        let y_vel = takeoff_f.velocity.y;
 
-        let vel_cutoff = JUMP_VELOCITIES.get(character as usize)
-            .expect("unknown character");
-        if y_vel > *vel_cutoff {
+        // these are the grounded jump values - double jump and float velocities are separate attributes.
+        let attributes = takeoff_f.character.attributes();
+        let vel_cutoff = (attributes.jump_force + attributes.short_hop_force) * 0.5;
+        if y_vel > vel_cutoff {
             Ok(JumpType::Full)
         } else {
             Ok(JumpType::Short)
@@ -558,23 +1177,71 @@ struct ActionInitData {
     pub start_state: BroadState,
     pub position: Vector,
     pub velocity: Vector,
+    pub l_cancelled: Option<bool>,
+    pub wavedash_angle: Option<f32>,
+    pub galint: Option<i8>,
+    pub pummels: u8,
+    pub di_angle: Option<f32>,
+    pub sdi_inputs: u8,
+    pub jab_hits: u8,
+    pub waveland_surface: Option<WavelandSurface>,
+    pub from_shield: bool,
+    pub oos_frames: Option<u8>,
+    pub grab_frames: Option<u16>,
+    pub inactionable_state: Option<ActionState>,
+    pub edge_cancelled: bool,
+    pub shield_frames: Option<u16>,
+}
+
+/// Facing and horizontal momentum both flipped soon after the first frame, from a b-reverse or wavebounce.
+fn is_b_reversed(frames: &[Frame]) -> bool {
+    const B_REVERSE_FRAMES: usize = 6;
+
+    let Some(before) = frames.first() else { return false };
+    frames.iter()
+        .take(B_REVERSE_FRAMES)
+        .any(|f| f.direction != before.direction && f.velocity.x * before.velocity.x < 0.0)
 }
 
 pub struct ActionBuilder<'a> {
+    all_frames: &'a [Frame],
     frames: &'a [Frame],
     cur_frame: usize,
     action_init_data: Option<ActionInitData>,
+    config: ParseConfig,
+
+    // only recorded by parse_traced
+    parsers: Option<Vec<&'static str>>,
 }
 
 impl<'a> ActionBuilder<'a> {
     pub fn new(frames: &'a [Frame]) -> Self {
+        Self::with_config(frames, ParseConfig::default())
+    }
+
+    pub fn with_config(frames: &'a [Frame], config: ParseConfig) -> Self {
+        Self::starting_at(frames, config, 0)
+    }
+
+    /// Frames before `start_frame` are treated as already consumed.
+    /// With `ParseConfig::skip_intro`, parsing never starts before `intro_end`.
+    pub fn starting_at(frames: &'a [Frame], config: ParseConfig, start_frame: usize) -> Self {
+        let mut start_frame = start_frame.min(frames.len());
+        if config.skip_intro { start_frame = start_frame.max(intro_end(frames)) }
         Self {
-            frames,
-            cur_frame: 0,
+            all_frames: frames,
+            frames: &frames[start_frame..],
+            cur_frame: start_frame,
             action_init_data: None,
+            config,
+            parsers: None,
         }
     }
 
+    pub fn config(&self) -> &ParseConfig {
+        &self.config
+    }
+
     pub fn current_frame(&self) -> usize {
         self.cur_frame
     }
@@ -591,14 +1258,123 @@ impl<'a> ActionBuilder<'a> {
             start_state,
             position,
             velocity,
+            l_cancelled: None,
+            wavedash_angle: None,
+            galint: None,
+            pummels: 0,
+            di_angle: None,
+            sdi_inputs: 0,
+            jab_hits: 0,
+            waveland_surface: None,
+            from_shield: false,
+            oos_frames: None,
+            grab_frames: None,
+            inactionable_state: None,
+            edge_cancelled: false,
+            shield_frames: None,
         });
 
         Ok(())
     }
 
+    pub fn set_l_cancelled(&mut self, l_cancelled: Option<bool>) {
+        let data = self.action_init_data.as_mut().expect("set l-cancel without starting action");
+        data.l_cancelled = l_cancelled;
+    }
+
+    pub fn set_edge_cancelled(&mut self, edge_cancelled: bool) {
+        let data = self.action_init_data.as_mut().expect("set edge cancel without starting action");
+        data.edge_cancelled = edge_cancelled;
+    }
+
+    pub fn set_wavedash_angle(&mut self, angle: Option<f32>) {
+        let data = self.action_init_data.as_mut().expect("set wavedash angle without starting action");
+        data.wavedash_angle = angle;
+    }
+
+    pub fn set_galint(&mut self, galint: Option<i8>) {
+        let data = self.action_init_data.as_mut().expect("set galint without starting action");
+        data.galint = galint;
+    }
+
+    pub fn set_pummels(&mut self, pummels: u8) {
+        let data = self.action_init_data.as_mut().expect("set pummels without starting action");
+        data.pummels = pummels;
+    }
+
+    pub fn set_di(&mut self, di_angle: Option<f32>, sdi_inputs: u8) {
+        let data = self.action_init_data.as_mut().expect("set di without starting action");
+        data.di_angle = di_angle;
+        data.sdi_inputs = sdi_inputs;
+    }
+
+    pub fn set_jab_hits(&mut self, jab_hits: u8) {
+        let data = self.action_init_data.as_mut().expect("set jab hits without starting action");
+        data.jab_hits = jab_hits;
+    }
+
+    pub fn set_waveland_surface(&mut self, surface: Option<WavelandSurface>) {
+        let data = self.action_init_data.as_mut().expect("set waveland surface without starting action");
+        data.waveland_surface = surface;
+    }
+
+    pub fn set_out_of_shield(&mut self, from_shield: bool, oos_frames: Option<u8>) {
+        let data = self.action_init_data.as_mut().expect("set out of shield without starting action");
+        data.from_shield = from_shield;
+        data.oos_frames = oos_frames;
+    }
+
+    pub fn set_shield_frames(&mut self, shield_frames: Option<u16>) {
+        let data = self.action_init_data.as_mut().expect("set shield frames without starting action");
+        data.shield_frames = shield_frames;
+    }
+
+    pub fn set_grab_frames(&mut self, grab_frames: Option<u16>) {
+        let data = self.action_init_data.as_mut().expect("set grab frames without starting action");
+        data.grab_frames = grab_frames;
+    }
+
+    pub fn set_inactionable_state(&mut self, inactionable_state: Option<ActionState>) {
+        let data = self.action_init_data.as_mut().expect("set inactionable state without starting action");
+        data.inactionable_state = inactionable_state;
+    }
+
+    /// Every frame, including those already consumed.
+    pub fn all_frames(&self) -> &'a [Frame] {
+        self.all_frames
+    }
+
+    /// Frames consumed by the current action so far.
+    pub fn action_frames(&self) -> &'a [Frame] {
+        let start = self.action_init_data.expect("no action started").action_start;
+        &self.all_frames[start..self.cur_frame]
+    }
+
+    /// Frames not yet consumed.
+    pub fn remaining_frames(&self) -> &'a [Frame] {
+        self.frames
+    }
+
     pub fn finish_action(&mut self, high_level_action: HighLevelAction) -> Action {
         let start_data = self.action_init_data.expect("finished action without starting");
 
+        // first actionable frame after the last inactionable frame of the action,
+        // which may be in the following action if its lag was not consumed
+        let action_frames = &self.all_frames[start_data.action_start..self.cur_frame];
+        let actionable_frame = match action_frames.iter().rposition(|f| !f.state.is_actionable()) {
+            Some(i) => {
+                let last_inactionable = start_data.action_start + i;
+                self.all_frames[last_inactionable..].iter()
+                    .position(|f| f.state.is_actionable())
+                    .map(|n| last_inactionable + n)
+                    .unwrap_or(self.all_frames.len())
+            }
+            None => start_data.action_start,
+        };
+
+        let b_reversed = matches!(high_level_action, HighLevelAction::Special(_))
+            && is_b_reversed(&self.all_frames[start_data.action_start.saturating_sub(1)..self.cur_frame]);
+
         Action {
             action_taken: high_level_action,
             frame_start: start_data.action_start,
@@ -606,6 +1382,23 @@ impl<'a> ActionBuilder<'a> {
             start_state: start_data.start_state,
             initial_position: start_data.position,
             initial_velocity: start_data.velocity,
+            l_cancelled: start_data.l_cancelled,
+            wavedash_angle: start_data.wavedash_angle,
+            galint: start_data.galint,
+            pummels: start_data.pummels,
+            di_angle: start_data.di_angle,
+            sdi_inputs: start_data.sdi_inputs,
+            jab_hits: start_data.jab_hits,
+            waveland_surface: start_data.waveland_surface,
+            actionable_frame,
+            from_shield: start_data.from_shield,
+            oos_frames: start_data.oos_frames,
+            shield_frames: start_data.shield_frames,
+            grab_frames: start_data.grab_frames,
+            inactionable_state: start_data.inactionable_state,
+            edge_cancelled: start_data.edge_cancelled,
+            b_reversed,
+            frames_since_prev: 0,
         }
     }
 
@@ -629,18 +1422,23 @@ impl<'a> ActionBuilder<'a> {
         self.next_frame().map(|f| f.state)
     }
 
-    pub fn next_frame<'b>(&'b mut self) -> Option<Frame> {
+    pub fn next_frame<'b>(&'b mut self) -> Option<&'a Frame> {
         match self.frames {
             [f, rs @ ..] => {
                 self.frames = rs;
                 self.cur_frame += 1;
-                Some(*f)
+                Some(f)
             }
             [] => None,
         }
     }
 
-    pub fn peek_frame<'b>(&'b mut self) -> Option<&'b Frame> {
+    /// The last frame consumed, if any.
+    pub fn prev_frame<'b>(&'b self) -> Option<&'a Frame> {
+        self.cur_frame.checked_sub(1).map(|i| &self.all_frames[i])
+    }
+
+    pub fn peek_frame<'b>(&'b self) -> Option<&'a Frame> {
         match self.frames {
             [f, ..] => {
                 Some(f)
@@ -688,3 +1486,399 @@ impl<'a> ActionBuilder<'a> {
         n
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::*;
+    use StandardActionState::*;
+
+    fn dash(direction: Direction, velocities: &[f32]) -> Vec<Frame> {
+        velocities.iter().enumerate().map(|(i, &x)| {
+            let mut f = frame(Dash);
+            f.anim_frame = i as f32 + 1.0;
+            f.direction = direction;
+            f.velocity.x = x;
+            f
+        }).collect()
+    }
+
+    fn taken(frames: &[Frame]) -> Vec<HighLevelAction> {
+        parse(frames).iter().map(|a| a.action_taken).collect()
+    }
+
+    #[test]
+    fn moonwalk() {
+        let mut frames = frames(&[(Wait, 5)]);
+        frames.extend(dash(Direction::Right, &[1.5, 1.5, 1.2, 0.8, 0.3, -0.4, -0.8, -0.8, -0.8, -0.8, -0.8, -0.8]));
+        frames.extend(crate::test_util::frames(&[(Wait, 10)]));
+
+        assert!(taken(&frames).contains(&HighLevelAction::Moonwalk(Direction::Left)));
+    }
+
+    #[test]
+    fn dash_back_is_not_moonwalk() {
+        let mut frames = frames(&[(Wait, 5)]);
+        frames.extend(dash(Direction::Right, &[1.5; 10]));
+        // carries rightwards momentum from the first dash
+        frames.extend(dash(Direction::Left, &[1.2, 0.6, -0.2, -0.9, -1.5, -1.5, -1.5, -1.5, -1.5, -1.5]));
+        frames.extend(crate::test_util::frames(&[(Wait, 10)]));
+
+        let taken = taken(&frames);
+        let dashes: Vec<_> = taken.iter().copied()
+            .filter(|a| matches!(a, HighLevelAction::DashLeft | HighLevelAction::DashRight | HighLevelAction::Moonwalk(_)))
+            .collect();
+        assert_eq!(dashes, [HighLevelAction::DashRight, HighLevelAction::DashLeft]);
+
+        let coalesced = crate::coalesce_dash_dances(&parse(&frames));
+        assert!(coalesced.iter().any(|a| a.action_taken == HighLevelAction::DashDance { count: 2 }));
+    }
+
+    fn jab_into_grab(jab_frames: usize) -> Vec<Action> {
+        parse(&frames(&[(Wait, 5), (Attack11, jab_frames), (Catch, 30), (Wait, 10)]))
+    }
+
+    #[test]
+    fn jab_grab_before_iasa_is_jab_cancel() {
+        let actions = jab_into_grab(6);
+        let jab = actions.iter().find(|a| a.frame_start == 5).unwrap();
+        assert_eq!(jab.action_taken, HighLevelAction::JabCancel);
+        assert_eq!(jab.jab_hits, 1);
+    }
+
+    #[test]
+    fn jab_grab_after_iasa_is_jab() {
+        let iasa = Character::Fox.jab_iasa()[0] as usize;
+        let actions = jab_into_grab(iasa);
+        let jab = actions.iter().find(|a| a.frame_start == 5).unwrap();
+        assert_eq!(jab.action_taken, HighLevelAction::GroundAttack(GroundAttack::Jab));
+    }
+
+    #[test]
+    fn repeated_jab_hits() {
+        // jab 1 restarts in the same state, then jab 2, then rapid jabs
+        let actions = parse(&frames(&[
+            (Wait, 5), (Attack11, 6), (Attack11, 6), (Attack12, 6),
+            (Attack100Start, 5), (Attack100Loop, 20), (Attack100End, 5), (Wait, 10),
+        ]));
+        let jab = actions.iter().find(|a| a.frame_start == 5).unwrap();
+        assert_eq!(jab.action_taken, HighLevelAction::GroundAttack(GroundAttack::Jab));
+        assert_eq!(jab.jab_hits, 3);
+        assert_eq!(jab.frame_end, 23);
+    }
+
+    fn after_pivot(runs: &[(StandardActionState, usize)]) -> Vec<HighLevelAction> {
+        let mut frames = frames(&[(Wait, 5), (Dash, 10), (Turn, 3)]);
+        frames.extend(crate::test_util::frames(runs));
+        parse(&frames).iter()
+            .map(|a| a.action_taken)
+            .skip_while(|a| *a != HighLevelAction::DashRight)
+            .skip(1)
+            .collect()
+    }
+
+    #[test]
+    fn empty_pivot() {
+        assert_eq!(after_pivot(&[(Wait, 30)])[0], HighLevelAction::EmptyPivot);
+    }
+
+    #[test]
+    fn pivot_tilt() {
+        let taken = after_pivot(&[(AttackS3S, 20), (Wait, 30)]);
+        assert_eq!(taken[..2], [HighLevelAction::Pivot, HighLevelAction::GroundAttack(GroundAttack::Ftilt)]);
+    }
+
+    #[test]
+    fn pivot_grab() {
+        let taken = after_pivot(&[(Catch, 8), (CatchPull, 2), (CatchWait, 30), (Wait, 30)]);
+        assert_eq!(taken[0], HighLevelAction::PivotGrab);
+
+        let taken = after_pivot(&[(Catch, 30), (Wait, 30)]);
+        assert_eq!(taken[0], HighLevelAction::GrabWhiff);
+    }
+
+    #[test]
+    fn standing_grab_is_not_pivot_grab() {
+        let taken: Vec<_> = parse(&frames(&[(Wait, 5), (Catch, 8), (CatchPull, 2), (CatchWait, 30), (Wait, 30)]))
+            .iter().map(|a| a.action_taken).collect();
+        assert!(taken.contains(&HighLevelAction::Grab));
+        assert!(!taken.contains(&HighLevelAction::PivotGrab));
+    }
+
+    #[test]
+    fn jump_type_from_takeoff_velocity() {
+        for character in [Character::Fox, Character::Peach, Character::Jigglypuff, Character::Kirby] {
+            let attributes = character.attributes();
+            for (y_vel, expected) in [
+                (attributes.short_hop_force, HighLevelAction::Shorthop),
+                (attributes.jump_force, HighLevelAction::Fullhop),
+            ] {
+                let mut frames = frames(&[(Wait, 5), (KneeBend, 3), (JumpF, 30), (Landing, 4), (Wait, 10)]);
+                for f in frames.iter_mut() {
+                    f.character = character;
+                    if f.state == ActionState::Standard(JumpF) {
+                        f.is_airborne = true;
+                        f.velocity.y = y_vel;
+                    }
+                }
+
+                let jump = parse(&frames).into_iter().find(|a| a.frame_start == 5).unwrap();
+                assert_eq!(jump.action_taken, expected, "{}", character);
+            }
+        }
+    }
+
+    fn airborne(mut frames: Vec<Frame>) -> Vec<Frame> {
+        for f in frames.iter_mut() { f.is_airborne = true }
+        frames
+    }
+
+    #[test]
+    fn recovery_into_ledge() {
+        use SpecialActionStateFox::*;
+        let fox = |st| ActionState::Special(SpecialActionState::Fox(st));
+
+        let mut frames = airborne(crate::test_util::frames(&[(Fall, 10)]));
+        frames.extend(airborne(run(fox(FireFoxAirStartup), 42)));
+        frames.extend(airborne(run(fox(FireFoxAir), 30)));
+        frames.extend(airborne(run(FallSpecial, 20)));
+        frames.extend(crate::test_util::frames(&[(CliffCatch, 7), (CliffWait, 30)]));
+
+        let taken: Vec<_> = parse(&frames).iter().map(|a| a.action_taken).collect();
+        let fire_fox = HighLevelAction::Special(SpecialHighLevelAction::Fox(HighLevelActionFox::FireFox));
+        assert_eq!(taken[1..4], [fire_fox, HighLevelAction::SpecialFall, HighLevelAction::LedgeWait]);
+    }
+
+    #[test]
+    fn pivot_fsmash() {
+        let taken = after_pivot(&[(AttackS4S, 40), (Wait, 30)]);
+        assert_eq!(taken[..2], [HighLevelAction::Pivot, HighLevelAction::GroundAttack(GroundAttack::Fsmash)]);
+    }
+
+    #[test]
+    fn walking_turnaround_is_not_pivot() {
+        let taken: Vec<_> = parse(&frames(&[(Wait, 5), (WalkMiddle, 20), (Turn, 3), (Wait, 30)]))
+            .iter().map(|a| a.action_taken).collect();
+        assert!(!taken.iter().any(|a| matches!(a, HighLevelAction::Pivot | HighLevelAction::EmptyPivot)));
+    }
+
+    fn shield_drop(stage: Stage) -> Vec<HighLevelAction> {
+        let mut frames = frames(&[(Wait, 5), (GuardOn, 8), (Guard, 10), (Pass, 5), (AttackAirN, 20), (Fall, 10)]);
+        for f in frames.iter_mut() {
+            f.position = Vector::new(40.0, 27.2); // battlefield side platform
+            f.is_airborne = matches!(f.state, ActionState::Standard(Pass | AttackAirN | Fall));
+        }
+        frames.extend(crate::test_util::frames(&[(Landing, 4), (Wait, 10)]));
+
+        let config = ParseConfig { stage: Some(stage), ..ParseConfig::default() };
+        parse_with_config(&frames, &config).iter().map(|a| a.action_taken).collect()
+    }
+
+    #[test]
+    fn shield_drop_aerial() {
+        let taken = shield_drop(Stage::Battlefield);
+        let drop = taken.iter().position(|a| *a == HighLevelAction::ShieldDrop).unwrap();
+        assert_eq!(taken[drop+1], HighLevelAction::Aerial(AirAttack::Nair));
+    }
+
+    #[test]
+    fn no_shield_drop_without_platforms() {
+        let on_fd = shield_drop(Stage::FinalDestination);
+        assert!(!on_fd.contains(&HighLevelAction::ShieldDrop));
+        assert!(on_fd.contains(&HighLevelAction::Shield));
+
+        // the stage is needed to find platforms
+        let frames = frames(&[(Wait, 5), (GuardOn, 8), (Guard, 10), (Pass, 5), (Fall, 10), (Landing, 4), (Wait, 10)]);
+        assert!(!taken(&frames).contains(&HighLevelAction::ShieldDrop));
+    }
+
+    #[test]
+    fn grab_pummels_back_throw() {
+        let actions = parse(&frames(&[
+            (Wait, 5), (Catch, 7), (CatchPull, 2), (CatchWait, 10), (CatchAttack, 6), (CatchWait, 4),
+            (CatchAttack, 6), (CatchWait, 4), (ThrowB, 30), (Wait, 10),
+        ]));
+        let throw = actions.iter().find(|a| a.frame_start == 5).unwrap();
+        assert_eq!(throw.action_taken, HighLevelAction::Throw(ThrowDirection::Back));
+        assert_eq!(throw.pummels, 2);
+    }
+
+    #[test]
+    fn whiffed_grab() {
+        let actions = parse(&frames(&[(Wait, 5), (Catch, 30), (Wait, 10)]));
+        let grab = actions.iter().find(|a| a.frame_start == 5).unwrap();
+        assert_eq!(grab.action_taken, HighLevelAction::GrabWhiff);
+        assert_eq!(grab.grab_frames, None);
+    }
+
+    #[test]
+    fn grab_release_and_throw_durations() {
+        // pulled for 2 frames, held for 20, then broken out of
+        let actions = parse(&frames(&[
+            (Wait, 5), (Catch, 7), (CatchPull, 2), (CatchWait, 20), (CatchCut, 30), (Wait, 10),
+        ]));
+        let release = actions.iter().find(|a| a.frame_start == 5).unwrap();
+        assert_eq!(release.action_taken, HighLevelAction::GrabRelease);
+        assert_eq!(release.grab_frames, Some(22));
+
+        let actions = parse(&frames(&[
+            (Wait, 5), (Catch, 7), (CatchPull, 2), (CatchWait, 20), (ThrowF, 30), (Wait, 10),
+        ]));
+        let throw = actions.iter().find(|a| a.frame_start == 5).unwrap();
+        assert_eq!(throw.action_taken, HighLevelAction::Throw(ThrowDirection::Forward));
+        assert_eq!(throw.grab_frames, Some(22));
+    }
+
+    fn getup(option: StandardActionState, direction: Direction) -> HighLevelAction {
+        let mut frames = frames(&[(Wait, 5), (DownBoundU, 26), (DownWaitU, 20), (option, 30), (Wait, 10)]);
+        for f in frames.iter_mut() { f.direction = direction }
+        parse(&frames).into_iter().find(|a| a.frame_start == 5).unwrap().action_taken
+    }
+
+    #[test]
+    fn missed_tech_getups() {
+        assert_eq!(getup(DownAttackU, Direction::Right), HighLevelAction::MissedTechAttack);
+        assert_eq!(getup(DownStandU, Direction::Right), HighLevelAction::MissedTechGetUp);
+        assert_eq!(getup(DownFowardU, Direction::Left), HighLevelAction::MissedTechRoll(Direction::Left));
+        assert_eq!(getup(DownBackU, Direction::Left), HighLevelAction::MissedTechRoll(Direction::Right));
+    }
+
+    #[test]
+    fn wavedash_angle() {
+        let mut frames = frames(&[(Wait, 5), (KneeBend, 3), (EscapeAir, 2)]);
+        for f in &mut frames[8..] {
+            f.is_airborne = true;
+            f.left_stick_coords = [0.7, -0.7];
+        }
+        let mut landing = run(LandingFallSpecial, 10);
+        for f in &mut landing {
+            f.velocity.x = 1.5;
+        }
+        frames.extend(landing);
+        frames.extend(run(Wait, 5));
+
+        let actions = parse(&frames);
+        let wavedash = actions.iter().find(|a| a.action_taken == HighLevelAction::WavedashRight).unwrap();
+        let angle = wavedash.wavedash_angle.unwrap();
+        assert!((angle + 45.0).abs() < 1e-4, "{}", angle);
+    }
+
+    #[test]
+    fn walking_turnaround() {
+        let mut frames = frames(&[(Wait, 5), (WalkMiddle, 20), (Turn, 3), (WalkMiddle, 20), (Wait, 30)]);
+        for f in &mut frames[25..48] {
+            f.direction = Direction::Left;
+        }
+
+        let taken = taken(&frames);
+        assert_eq!(taken, [
+            HighLevelAction::GroundWait,
+            HighLevelAction::WalkRight,
+            HighLevelAction::WalkLeft,
+            HighLevelAction::GroundWait,
+        ]);
+    }
+
+    #[test]
+    fn wall_techs() {
+        for (tech, hla) in [(PassiveWall, HighLevelAction::TechWall), (PassiveWallJump, HighLevelAction::TechWallJump)] {
+            let mut frames = frames(&[(Wait, 5), (tech, 20), (Fall, 10), (Landing, 4), (Wait, 10)]);
+            for f in &mut frames[5..35] { f.is_airborne = true }
+            let tech = parse(&frames).into_iter().find(|a| a.frame_start == 5).unwrap();
+            assert_eq!(tech.action_taken, hla);
+        }
+    }
+
+    #[test]
+    fn edge_cancelled_aerial() {
+        fn aerial(landing_x: f32, after_lag: StandardActionState) -> Action {
+            let mut frames = frames(&[(Fall, 5), (AttackAirN, 10), (LandingAirN, 3), (after_lag, 10), (Landing, 4), (Wait, 10)]);
+            for (i, f) in frames.iter_mut().enumerate() {
+                f.is_airborne = !matches!(f.state, ActionState::Standard(LandingAirN | Landing | Wait));
+                if i < 18 {
+                    // battlefield right platform
+                    f.position = Vector::new(landing_x, 27.2);
+                }
+            }
+
+            let config = ParseConfig { stage: Some(Stage::Battlefield), ..ParseConfig::default() };
+            parse_with_config(&frames, &config).into_iter()
+                .find(|a| a.action_taken.is_attack())
+                .unwrap()
+        }
+
+        assert!(aerial(56.0, Fall).edge_cancelled);
+        // airborne again away from any platform edge
+        assert!(!aerial(40.0, Fall).edge_cancelled);
+        // stayed on the platform
+        assert!(!aerial(56.0, Wait).edge_cancelled);
+    }
+
+    #[test]
+    fn reverse_aerial_rush() {
+        fn bair(turnaround: bool) -> Vec<HighLevelAction> {
+            let mut frames = frames(&[
+                (Wait, 5), (Dash, 10), (Run, 5), (TurnRun, if turnaround { 6 } else { 0 }),
+                (KneeBend, 3), (JumpF, 2), (AttackAirB, 20), (Fall, 5), (Landing, 4), (Wait, 10),
+            ]);
+            for f in &mut frames[5..] {
+                f.velocity.x = 1.2;
+                f.is_airborne = matches!(f.state, ActionState::Standard(JumpF | AttackAirB | Fall));
+                if turnaround && matches!(f.state, ActionState::Standard(TurnRun | KneeBend | JumpF | AttackAirB | Fall)) {
+                    f.direction = Direction::Left;
+                }
+            }
+            taken(&frames)
+        }
+
+        assert!(bair(true).contains(&HighLevelAction::ReverseAerialRush(AirAttack::Bair)));
+        assert!(!bair(false).iter().any(|a| matches!(a, HighLevelAction::ReverseAerialRush(_))));
+    }
+
+    #[test]
+    fn b_reverse() {
+        let mut frames = run(Fall, 8);
+        for f in &mut frames { f.velocity.x = 1.0 }
+        assert!(!is_b_reversed(&frames));
+
+        for f in &mut frames[2..] {
+            f.direction = Direction::Left;
+            f.velocity.x = -1.0;
+        }
+        assert!(is_b_reversed(&frames));
+
+        // turning around without reversing momentum is not a b-reverse
+        for f in &mut frames[2..] { f.velocity.x = 1.0 }
+        assert!(!is_b_reversed(&frames));
+    }
+
+    #[test]
+    fn parse_range_mid_action() {
+        let frames = frames(&[(Wait, 5), (AttackS4S, 40), (Wait, 20), (Dash, 10), (Wait, 20)]);
+
+        let actions = parse_range(&frames, 10, frames.len());
+        assert_eq!(actions[0].frame_start, 45);
+        assert_eq!(actions[0].action_taken, HighLevelAction::GroundWait);
+        assert!(!actions.iter().any(|a| a.action_taken == HighLevelAction::GroundAttack(GroundAttack::Fsmash)));
+
+        // starting on an actionable frame parses from there
+        let actions = parse_range(&frames, 0, 45);
+        assert_eq!(actions[0].frame_start, 0);
+        assert!(actions.iter().all(|a| a.frame_end <= 45));
+    }
+
+    #[test]
+    fn parse_range_with_stage() {
+        let mut frames = frames(&[(Wait, 5), (GuardOn, 8), (Guard, 10), (Pass, 5), (Fall, 10)]);
+        for f in frames.iter_mut() {
+            f.position = Vector::new(40.0, 27.2); // battlefield side platform
+            f.is_airborne = matches!(f.state, ActionState::Standard(Pass | Fall));
+        }
+        frames.extend(crate::test_util::frames(&[(Landing, 4), (Wait, 10)]));
+
+        let config = ParseConfig { stage: Some(Stage::Battlefield), ..ParseConfig::default() };
+        let taken: Vec<_> = parse_range_with_config(&frames, &config, 2, frames.len())
+            .iter().map(|a| a.action_taken).collect();
+        assert_eq!(taken, [HighLevelAction::ShieldDrop, HighLevelAction::AirWait, HighLevelAction::GroundWait]);
+    }
+}
@@ -13,6 +13,9 @@ pub use game_enums::*;
 mod shift_jis_decoder;
 pub use shift_jis_decoder::*;
 
+#[cfg(test)]
+mod test_util;
+
 use std::path::Path;
 
 pub type SlpResult<T> = Result<T, SlpError>;
@@ -22,13 +25,18 @@ pub enum SlpError {
     OutdatedFile,
     InvalidFile,
     NotTwoPlayers,
+    MissingPort(u8),
     UnimplementedCharacter(Character),
+    UnknownStage(u16),
+    UnknownCharacter(u8),
+    CompressedFile, // the flate2 feature is required to read compressed files
 
     FileDoesNotExist,
     IOError,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Action {
     pub start_state: BroadState,
     pub action_taken: HighLevelAction,
@@ -36,16 +44,83 @@ pub struct Action {
     pub frame_end: usize,
     pub initial_position: Vector,
     pub initial_velocity: Vector,
+
+    // only set for aerials that end in a landing
+    pub l_cancelled: Option<bool>,
+
+    // specials that flipped facing and horizontal momentum in their first frames, from a b-reverse or wavebounce
+    pub b_reversed: bool,
+
+    // aerials whose landing lag was cut short by sliding off the stage or a platform edge.
+    // requires ParseConfig::stage
+    pub edge_cancelled: bool,
+
+    // degrees, from the stick on the first airdodge frame. 0 is right, -90 is down.
+    // only set for wavedashes and wavelands with a non-neutral stick
+    pub wavedash_angle: Option<f32>,
+
+    // intangible frames remaining once actionable, negative if vulnerable beforehand.
+    // only set for ledgedashes
+    pub galint: Option<i8>,
+
+    // only nonzero for grabs and throws
+    pub pummels: u8,
+
+    // degrees, from the stick on the last frame of the first hitlag. 0 is right, 90 is up.
+    // only set for hitstun with a non-neutral stick
+    pub di_angle: Option<f32>,
+
+    // only nonzero for hitstun
+    pub sdi_inputs: u8,
+
+    // jab 1, 2 and 3 hits, not including rapid jabs.
+    // only nonzero for jabs and jab cancels
+    pub jab_hits: u8,
+
+    // only set for wavelands
+    pub waveland_surface: Option<WavelandSurface>,
+
+    // First frame the player could freely act again, after any endlag or landing lag.
+    // frame_end is where the next action begins, which is not always actionable:
+    // landing lag is left to the following action, so this can be after frame_end.
+    // Equal to frame_start if the action never left an actionable state.
+    pub actionable_frame: usize,
+
+    // rolls, spotdodges, grabs, jumps and wavedashes performed directly out of shield
+    pub from_shield: bool,
+
+    // frames between the end of shieldstun and the out of shield option.
+    // only set if from_shield and the shield was hit
+    pub oos_frames: Option<u8>,
+
+    // frames spent shielding before the out of shield option, including GuardOff.
+    // only set if from_shield
+    pub shield_frames: Option<u16>,
+
+    // frames the opponent was held, from the pull until the throw or release.
+    // only set for grabs, grab releases and throws
+    pub grab_frames: Option<u16>,
+
+    // first state of the skipped segment, only set for Inactionable
+    pub inactionable_state: Option<ActionState>,
+
+    // frames between the end of the previous action and the start of this one,
+    // spent in states that are not parsed into actions. The first action is measured from game start.
+    pub frames_since_prev: usize,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Port {
     Low = 0,
     High = 1,
 }
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Frame {
+    // Read from the internal character id every frame, 
+    // so this switches between Sheik (0x07) and Zelda (0x13) on transformation.
     pub character: Character,
     pub port_idx: u8, // zero indexed
     pub direction: Direction,
@@ -56,38 +131,136 @@ pub struct Frame {
     pub state: ActionState,
     pub state_num: u16,
     pub anim_frame: f32,
-    pub shield_size: f32,
+    pub shield_size: f32, // shield health, 60 when full
 
     // controls
     pub buttons_mask: ButtonsMask,
     pub analog_trigger_value: f32,
     pub left_stick_coords: [f32; 2], // processed values
     pub right_stick_coords: [f32; 2],
+    pub raw_analog_x: i8, // zero if not in replay
+    pub rng_seed: u32, // before this frame is processed
 
     pub percent: f32,
-    pub stock_count: u8,
+    pub stock_count: u8, // stocks remaining
     pub is_airborne: bool,
     pub hitlag_frames: f32,
+    pub l_cancel: Option<bool>, // set on landing from an aerial
+    pub hurtbox_state: HurtboxState,
+
+    // only set for ice climbers while nana is alive
+    pub nana: Option<NanaFrame>,
+}
+
+/// Nana's state on a frame. Popo's is in the `Frame` itself.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NanaFrame {
+    pub direction: Direction,
+    pub velocity: Vector,
+    pub position: Vector,
+    pub state: ActionState,
+    pub state_num: u16,
+    pub percent: f32,
+    pub is_airborne: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HurtboxState {
+    Vulnerable,
+    Invulnerable,
+    Intangible,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Inputs {
+    pub buttons: ButtonsMask,
+    pub stick: Vector,
+    pub cstick: Vector,
+    pub trigger: f32,
+    pub raw_analog_x: i8,
+}
+
+/// Stick coordinates below this on both axes are treated as neutral.
+pub const STICK_DEADZONE: f32 = 0.2875;
+
+impl Frame {
+    pub fn inputs(&self) -> Inputs {
+        Inputs {
+            buttons: self.buttons_mask,
+            stick: Vector { x: self.left_stick_coords[0], y: self.left_stick_coords[1] },
+            cstick: Vector { x: self.right_stick_coords[0], y: self.right_stick_coords[1] },
+            trigger: self.analog_trigger_value,
+            raw_analog_x: self.raw_analog_x,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Item {
     pub type_id: u16,
     pub state: u8,
     pub direction: Direction,
     pub position: Vector,
+    pub velocity: Vector,
     pub missile_type: u8,
     pub turnip_type: u8,
     pub charge_shot_launched: bool,
     pub charge_shot_power: u8,
     pub spawn_id: u32,
-    pub owner: i8,
+    pub owner: Option<u8>, // port idx, None if unowned
+}
+
+/// An item tracked by spawn id from the first to the last frame it exists.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ItemLife {
+    pub spawn_id: u32,
+    pub type_id: u16,
+    pub owner: Option<u8>, // port idx, None if unowned
+    pub spawn_frame: usize,
+    pub despawn_frame: usize, // first frame the item no longer exists
+    pub trajectory: Vec<Vector>, // position on each frame, starting at spawn_frame
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ProjectileEventKind {
+    Laser,
+    Reflect,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProjectileEvent {
+    pub kind: ProjectileEventKind,
+    pub frame: usize,
+    pub spawn_id: u32, // the fired laser or the reflected item
+    pub position: Vector, // item position on this frame
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerInfo {
+    pub port_idx: u8, // zero indexed
+    pub starting_character: CharacterColour,
+    pub team_id: Option<u8>, // None if not a teams match
 }
 
 // requires parsing metadata
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GameInfo {
     pub stage: Stage,
+
+    // indexed by port, None if the port is empty
+    pub players: [Option<PlayerInfo>; 4],
+
+    // the low and high fields refer to the first two present ports.
+    // prefer `players` for doubles.
     pub low_port_idx: u8,
     pub low_starting_character: CharacterColour,
     pub high_port_idx: u8,
@@ -102,11 +275,89 @@ pub struct GameInfo {
     // null terminated Shift JIS strings. zero length if does not exist
     pub low_connect_code: [u8; 10],
     pub high_connect_code: [u8; 10],
+
+    // null terminated Shift JIS strings. zero length if does not exist
+    pub low_nametag: [u8; 16],
+    pub high_nametag: [u8; 16],
+
+    pub random_seed: u32, // starting seed
+
+    pub slippi_version: (u8, u8, u8), // major, minor, build
+    pub is_pal: bool,
+    pub is_frozen_ps: bool, // frozen pokemon stadium
+
+    pub starting_stocks: u8,
+    pub time_limit_seconds: Option<u32>, // None if there is no timer
+    pub items_enabled: bool,
+}
+
+impl GameInfo {
+    /// Zero indexed ports with a player, in ascending order.
+    pub fn ports(&self) -> impl Iterator<Item = u8> + '_ {
+        self.players.iter().flatten().map(|p| p.port_idx)
+    }
+
+    /// Zero indexed controller port of the low or high player.
+    /// For players on controller ports 2 and 4, `Port::Low` is 1 and `Port::High` is 3.
+    pub fn port_for(&self, port: Port) -> u8 {
+        match port {
+            Port::Low => self.low_port_idx,
+            Port::High => self.high_port_idx,
+        }
+    }
+
+    /// Low or high for a zero indexed controller port. None if the port is not one of the first two present ports.
+    pub fn which_port(&self, port_idx: u8) -> Option<Port> {
+        if port_idx == self.low_port_idx {
+            Some(Port::Low)
+        } else if port_idx == self.high_port_idx {
+            Some(Port::High)
+        } else {
+            None
+        }
+    }
+
+    /// Online display name. None if empty or not in replay.
+    pub fn display_name(&self, port: Port) -> Option<String> {
+        match port {
+            Port::Low => decode_info_string(&self.low_name),
+            Port::High => decode_info_string(&self.high_name),
+        }
+    }
+
+    /// Online connect code. None if empty or not in replay.
+    pub fn connect_code(&self, port: Port) -> Option<String> {
+        match port {
+            Port::Low => decode_info_string(&self.low_connect_code),
+            Port::High => decode_info_string(&self.high_connect_code),
+        }
+    }
+
+    /// In-game nametag. None if empty or not in replay.
+    pub fn nametag(&self, port: Port) -> Option<String> {
+        match port {
+            Port::Low => decode_info_string(&self.low_nametag),
+            Port::High => decode_info_string(&self.high_nametag),
+        }
+    }
+}
+
+fn decode_info_string(bytes: &[u8]) -> Option<String> {
+    let mut s = String::new();
+    decode_shift_jis(bytes, &mut s)?;
+    if s.is_empty() { None } else { Some(s) }
 }
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GameStartInfo {
     pub stage: Stage,
+
+    // indexed by port, None if the port is empty
+    pub players: [Option<PlayerInfo>; 4],
+
+    // the low and high fields refer to the first two present ports.
+    // prefer `players` for doubles.
     pub low_port_idx: u8,
     pub low_starting_character: CharacterColour,
     pub high_port_idx: u8,
@@ -119,12 +370,28 @@ pub struct GameStartInfo {
     // null terminated Shift JIS strings. zero length if does not exist
     pub low_connect_code: [u8; 10],
     pub high_connect_code: [u8; 10],
+
+    // null terminated Shift JIS strings. zero length if does not exist
+    pub low_nametag: [u8; 16],
+    pub high_nametag: [u8; 16],
+
+    pub random_seed: u32, // starting seed
+
+    pub slippi_version: (u8, u8, u8), // major, minor, build
+    pub is_pal: bool,
+    pub is_frozen_ps: bool, // frozen pokemon stadium
+
+    pub starting_stocks: u8,
+    pub time_limit_seconds: Option<u32>, // None if there is no timer
+    pub items_enabled: bool,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Game {
-    pub low_port_frames: Box<[Frame]>,
-    pub high_port_frames: Box<[Frame]>,
+    /// One entry per present port, in ascending port order.
+    /// `Port::Low` and `Port::High` refer to the first two entries.
+    pub players: Vec<PlayerFrames>,
 
     /// get item_range with `item_idx[frame]..item_idx[frame+1]`
     pub item_idx: Box<[u16]>,
@@ -132,16 +399,51 @@ pub struct Game {
     pub info: GameInfo,
 
     pub stage_info: Option<StageInfo>,
+    pub result: GameResult,
+
+    /// Slippi frame number of the first frame, usually -123.
+    pub first_frame: i32,
+
+    /// First word of each active gecko code, holding the code type and address.
+    /// Empty if the replay has no gecko list.
+    pub gecko_codes: Vec<u32>,
 } 
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerFrames {
+    pub port_idx: u8, // zero indexed
+    pub frames: Box<[Frame]>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameResult {
+    pub end_method: GameEndMethod,
+    pub winner_port: Option<u8>, // zero indexed
+    pub lras_initiator: Option<u8>, // zero indexed
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameEndMethod {
+    Unresolved, // or game end event missing
+    Time,
+    Game,
+    Resolved, // older replays
+    NoContest, // LRAS
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FountainHeights {
     // (frame, height)
     pub heights_l: Vec<(i32, f32)>,
     pub heights_r: Vec<(i32, f32)>,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StadiumTransformation {
     Normal,
     Grass,
@@ -151,23 +453,88 @@ pub enum StadiumTransformation {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StadiumTransformations {
     // (frame, new transformation)
     pub events: Vec<(i32, StadiumTransformation)>,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StageInfo {
     Fountain(FountainHeights),
     Stadium(StadiumTransformations),
 }
 
 impl Game {
+    pub const DEFAULT_FIRST_FRAME: i32 = -123;
+
+    /// Converts a frame index, such as `Action::frame_start`, into a Slippi frame number.
+    pub fn absolute_frame(&self, idx: usize) -> i32 {
+        self.first_frame + idx as i32
+    }
+
+    /// Index of the first frame where every player has finished their entry animation.
+    /// Time based stats should use this as the start of gameplay.
+    pub fn gameplay_start_frame(&self) -> usize {
+        self.players.iter()
+            .map(|p| intro_end(&p.frames))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Default config for this game's stage.
+    pub fn parse_config(&self) -> ParseConfig {
+        ParseConfig {
+            stage: Some(self.info.stage),
+            ..ParseConfig::default()
+        }
+    }
+
+    /// Consumes the game, keeping only the frames for `port`.
+    pub fn take_port(mut self, port: Port) -> Box<[Frame]> {
+        self.players.swap_remove(port as usize).frames
+    }
+
+    pub fn port_frames(&self, port: Port) -> &[Frame] {
+        &self.players[port as usize].frames
+    }
+
+    /// Frames for the player in port `port_idx` (zero indexed). None if the port is empty.
+    pub fn player_frames(&self, port_idx: u8) -> Option<&[Frame]> {
+        self.players.iter()
+            .find(|p| p.port_idx == port_idx)
+            .map(|p| &*p.frames)
+    }
+
+    /// Yields each frame with its Slippi frame number.
+    pub fn frames(&self, port: Port) -> impl Iterator<Item = (i32, &Frame)> + '_ {
+        let first_frame = self.first_frame;
+        self.port_frames(port)
+            .iter()
+            .enumerate()
+            .map(move |(i, f)| (first_frame + i as i32, f))
+    }
+
+    /// Frame at a Slippi frame number.
+    pub fn frame_at(&self, port: Port, melee_frame: i32) -> Option<&Frame> {
+        let idx = melee_frame.checked_sub(self.first_frame)?;
+        let idx: usize = idx.try_into().ok()?;
+        self.port_frames(port).get(idx)
+    }
+
     pub fn items_on_frame(&self, frame: usize) -> &[Item] {
         let start = self.item_idx[frame] as usize;
         let end = self.item_idx[frame+1] as usize;
         &self.items[start..end]
     }
+
+    /// Items owned by the player in port `port_idx` (zero indexed) on this frame.
+    pub fn player_items(&self, port_idx: u8, frame: usize) -> impl Iterator<Item = &Item> + '_ {
+        self.items_on_frame(frame)
+            .iter()
+            .filter(move |item| item.owner == Some(port_idx))
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -177,11 +544,232 @@ pub struct InteractionRef<'a> {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Interaction {
     pub opponent_initiation: Action,
     pub player_response: Action,
 }
 
+impl<'a> InteractionRef<'a> {
+    pub fn to_interaction(&self) -> Interaction {
+        Interaction {
+            opponent_initiation: self.opponent_initiation.clone(),
+            player_response: self.player_response.clone(),
+        }
+    }
+
+    /// Frames between the opponent's initiation and the player's response.
+    pub fn advantage_frames(&self) -> i64 {
+        self.player_response.frame_start as i64 - self.opponent_initiation.frame_start as i64
+    }
+
+    /// Whether the player faced the opponent when responding. See `facing_opponent`.
+    /// None if either slice of frames does not reach the response.
+    pub fn player_facing_opponent(&self, player_frames: &[Frame], opponent_frames: &[Frame]) -> Option<bool> {
+        let i = self.player_response.frame_start;
+        Some(facing_opponent(player_frames.get(i)?, opponent_frames.get(i)?))
+    }
+}
+
+impl Interaction {
+    /// Frames between the opponent's initiation and the player's response.
+    pub fn advantage_frames(&self) -> i64 {
+        self.player_response.frame_start as i64 - self.opponent_initiation.frame_start as i64
+    }
+
+    /// See `InteractionRef::player_facing_opponent`.
+    pub fn player_facing_opponent(&self, player_frames: &[Frame], opponent_frames: &[Frame]) -> Option<bool> {
+        InteractionRef {
+            opponent_initiation: &self.opponent_initiation,
+            player_response: &self.player_response,
+        }.player_facing_opponent(player_frames, opponent_frames)
+    }
+}
+
+/// Whether the player is facing towards the opponent.
+/// Always true if they share an x position.
+/// Facing is the character's direction, so turning around to bair counts as facing away.
+pub fn facing_opponent(player_frame: &Frame, opponent_frame: &Frame) -> bool {
+    let dx = opponent_frame.position.x - player_frame.position.x;
+    match player_frame.direction {
+        _ if dx == 0.0 => true,
+        Direction::Right => dx > 0.0,
+        Direction::Left => dx < 0.0,
+    }
+}
+
+/// Units of knockback per unit of launch speed.
+pub const KNOCKBACK_PER_VELOCITY: f32 = 1.0 / 0.03;
+
+/// Melee's hitstun, 0.4 frames per unit of knockback.
+/// Replays do not store knockback. Estimate it from the defender's launch speed with `knockback_from_velocity`.
+pub fn hitstun_frames(knockback: f32) -> u32 {
+    (knockback * 0.4).floor().max(0.0) as u32
+}
+
+/// Knockback from the defender's `hit_velocity` on the first frame after hitlag.
+pub fn knockback_from_velocity(hit_velocity: Vector) -> f32 {
+    hit_velocity.magnitude() * KNOCKBACK_PER_VELOCITY
+}
+
+/// For each attacker action, whether it hit the defender before they escaped the hitstun of the previous hit.
+/// Actions that did not hit are false.
+pub fn is_true_combo(attacker: &[Action], defender_frames: &[Frame]) -> Vec<bool> {
+    let hit_at = |i: usize| defender_hit_at(defender_frames, i);
+
+    attacker.iter()
+        .map(|a| {
+            let end = a.frame_end.min(defender_frames.len());
+            let Some(hit) = (a.frame_start..end).find(|&i| hit_at(i)) else { return false };
+            let Some(prev_hit) = (0..hit).rev().find(|&i| hit_at(i)) else { return false };
+            hit <= defender_hitstun_end(defender_frames, prev_hit)
+        })
+        .collect()
+}
+
+/// A gap in a punish where the defender left hitstun before the attacker's next hit.
+/// See `dropped_combos`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DroppedCombo {
+    pub prev_hit: usize, // frame of the hit the defender escaped from
+    pub hitstun_end: usize, // last frame of hitstun from prev_hit
+    pub next_hit: usize,
+    pub action_idx: usize, // index into the attacker's actions of the action landing next_hit
+}
+
+/// Hits continuing a punish that were not true combos, so the defender could have escaped.
+/// Hits more than `PUNISH_RESET_FRAMES` after the previous hitstun ended start a new punish, so are not included.
+pub fn dropped_combos(attacker: &[Action], defender_frames: &[Frame]) -> Vec<DroppedCombo> {
+    let hit_at = |i: usize| defender_hit_at(defender_frames, i);
+
+    attacker.iter()
+        .enumerate()
+        .filter_map(|(action_idx, a)| {
+            let end = a.frame_end.min(defender_frames.len());
+            let next_hit = (a.frame_start..end).find(|&i| hit_at(i))?;
+            let prev_hit = (0..next_hit).rev().find(|&i| hit_at(i))?;
+            let hitstun_end = defender_hitstun_end(defender_frames, prev_hit);
+
+            let dropped = hitstun_end < next_hit && next_hit <= hitstun_end + PUNISH_RESET_FRAMES;
+            dropped.then_some(DroppedCombo { prev_hit, hitstun_end, next_hit, action_idx })
+        })
+        .collect()
+}
+
+fn defender_hit_at(defender_frames: &[Frame], i: usize) -> bool {
+    i > 0 && defender_frames[i].percent > defender_frames[i-1].percent
+}
+
+/// Last frame the defender is still in hitstun from a hit on frame i.
+fn defender_hitstun_end(defender_frames: &[Frame], i: usize) -> usize {
+    let hitlag_end = defender_frames[i..].iter()
+        .position(|f| f.hitlag_frames <= 0.0)
+        .map_or(defender_frames.len() - 1, |n| i + n);
+    let knockback = knockback_from_velocity(defender_frames[hitlag_end].hit_velocity);
+    hitlag_end + hitstun_frames(knockback) as usize
+}
+
+/// A string of hits on the defender.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Punish {
+    pub frame_start: usize,
+    pub frame_end: usize,
+    pub moves: u32, // attacks by the punisher during the punish
+    pub damage: f32,
+    pub killed: bool,
+}
+
+/// Repeated ledge grabs without touching the stage.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LedgeStall {
+    pub frame_start: usize, // first grab
+    pub frame_end: usize, // after leaving ledge for the last time
+    pub grabs: u32,
+}
+
+/// Frames spent on each surface. See `surface_occupancy`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SurfaceStats {
+    pub main_stage: u32,
+    pub platforms: Vec<u32>, // indexed the same as `Stage::platforms`
+    pub ledge: u32,
+    pub airborne: u32,
+}
+
+/// A light hit on a downed defender, forcing them to stand up.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JabReset {
+    pub frame: usize, // frame the reset hit landed
+    pub getup_frame: usize, // frame the forced getup started
+    pub followup_frame: Option<usize>, // next hit, if within PUNISH_RESET_FRAMES of the getup
+}
+
+/// Punish efficiency over a game. See `conversion_stats`.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConversionStats {
+    pub openings: u32, // punishes, including counter attacks
+    pub neutral_wins: u32,
+    pub kills: u32, // punishes that took a stock
+    pub total_damage: f32,
+    pub damage_per_opening: Option<f32>, // None if there were no openings
+    pub openings_per_kill: Option<f32>, // None if there were no kills
+}
+
+/// Counts of each ledge option. See `ledge_option_stats`.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LedgeStats {
+    pub waits: u32,
+    pub ledgedashes: u32,
+    pub getups: u32,
+    pub rolls: u32,
+    pub jumps: u32,
+    pub hops: u32,
+    pub aerials: u32,
+    pub attacks: u32,
+    pub drops: u32,
+    pub stalls: u32, // each stall is made of several of the other options
+    pub average_galint: Option<f32>, // over ledgedashes with a known galint
+}
+
+impl LedgeStats {
+    /// Options taken from the ledge, not including waiting or stalls.
+    pub fn total(&self) -> u32 {
+        self.ledgedashes + self.getups + self.rolls + self.jumps 
+            + self.hops + self.aerials + self.attacks + self.drops
+    }
+}
+
+/// Time spent off-stage trying to get back.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecoverySegment {
+    pub frame_start: usize,
+    pub frame_end: usize,
+    pub furthest_x: f32, // x position furthest from the stage
+    pub min_y: f32,
+    pub jumps: u32,
+    pub specials: u32,
+    // wall techs and wall tech jumps. Always zero on stages without walls, such as Battlefield.
+    pub wall_techs: u32,
+    pub outcome: RecoveryOutcome,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RecoveryOutcome {
+    Ledge,
+    Landed,
+    Died,
+    Unfinished, // frames ended while off-stage
+}
+
 #[derive(Clone, Debug)]
 pub struct SlpFileInfo {
     pub path: Box<Path>,
@@ -274,6 +862,85 @@ pub fn read_info_in_dir(
     Ok(())
 }
 
+/// Like `read_info_in_dir`, but reads files across threads.
+/// Files that fail to parse are returned along with their error rather than aborting the batch.
+#[cfg(feature = "rayon")]
+pub fn read_info_in_dir_par(
+    path: impl AsRef<Path>,
+    prev: &mut SlpDirectoryInfo
+) -> SlpResult<Vec<(Box<Path>, SlpError)>> {
+    use rayon::prelude::*;
+
+    prev.slp_files.clear();
+    prev.folders.clear();
+    let mut hash = 0;
+
+    let mut game_paths = Vec::new();
+    for entry in std::fs::read_dir(path).map_err(|_| SlpError::IOError)? {
+        let entry = entry.map_err(|_| SlpError::IOError)?;
+        match entry_type(&entry)? {
+            SlpDirEntryType::SlpFile => {
+                let game_path = entry.path();
+                hash ^= simple_hash(game_path.as_os_str().as_encoded_bytes());
+                game_paths.push(game_path.into_boxed_path());
+            }
+            SlpDirEntryType::Directory => {
+                let folder_path = entry.path();
+                hash ^= simple_hash(folder_path.as_os_str().as_encoded_bytes());
+                prev.folders.push(Folder {
+                    path: folder_path.into_boxed_path(),
+                });
+            }
+            _ => (),
+        }
+    }
+
+    let results: Vec<_> = game_paths.into_par_iter()
+        .map(|path| {
+            let info = read_info(&path);
+            (path, info)
+        })
+        .collect();
+
+    let mut errors = Vec::new();
+    for (path, info) in results {
+        match info {
+            Ok(info) => prev.slp_files.push(SlpFileInfo { path, info }),
+            Err(e) => errors.push((path, e)),
+        }
+    }
+
+    prev.dir_hash = hash;
+
+    Ok(errors)
+}
+
+/// Each file's path with the actions parsed from it, or the error that stopped it.
+#[cfg(feature = "rayon")]
+pub type DirParseResults = Vec<(Box<Path>, SlpResult<Box<[Action]>>)>;
+
+/// Parses actions for every slp file in a directory across threads. Not recursive.
+/// Each file's result is returned with its path, so errors do not abort the batch.
+#[cfg(feature = "rayon")]
+pub fn parse_dir_par(path: impl AsRef<Path>, port: Port) -> SlpResult<DirParseResults> {
+    use rayon::prelude::*;
+
+    let mut game_paths = Vec::new();
+    for entry in std::fs::read_dir(path).map_err(|_| SlpError::IOError)? {
+        let entry = entry.map_err(|_| SlpError::IOError)?;
+        if let SlpDirEntryType::SlpFile = entry_type(&entry)? {
+            game_paths.push(entry.path().into_boxed_path());
+        }
+    }
+
+    Ok(game_paths.into_par_iter()
+        .map(|path| {
+            let actions = parse_game(&path, port);
+            (path, actions)
+        })
+        .collect())
+}
+
 pub fn dir_hash(path: impl AsRef<Path>) -> SlpResult<u64> {
     let mut hash = 0;
 
@@ -319,15 +986,43 @@ pub fn read_info(path: &Path) -> SlpResult<GameInfo> {
     Ok(info)
 }
 
+/// Number of frames in a replay, without building the `Game`.
+/// Reads `lastFrame` from the metadata, falling back to scanning frame events if it is missing.
+/// None if the file cannot be read.
+pub fn frame_count(path: &Path) -> Option<u32> {
+    let file = std::fs::File::open(path).ok()?;
+    file_parser::parse_frame_count(&mut std::io::BufReader::new(file)).ok()
+}
+
+/// Like `read_info`, but for a file already in memory, such as in wasm.
+pub fn read_info_from_bytes(buf: &[u8]) -> SlpResult<GameInfo> {
+    file_parser::parse_file_info(&mut std::io::Cursor::new(buf))
+}
+
 pub fn read_game(path: &Path) -> SlpResult<(Game, Notes)> {
     use std::io::Read;
 
     let mut file = std::fs::File::open(path).map_err(|_| SlpError::FileDoesNotExist)?;
     let mut buf = Vec::new();
-    file.read_to_end(&mut buf).unwrap();
+    file.read_to_end(&mut buf).map_err(|_| SlpError::IOError)?;
+
+    read_game_from_bytes(&buf)
+}
+
+/// Cheap integrity check of a replay without parsing frames.
+pub fn validate(path: &Path) -> SlpResult<ValidationReport> {
+    use std::io::Read;
+
+    let mut file = std::fs::File::open(path).map_err(|_| SlpError::FileDoesNotExist)?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf).map_err(|_| SlpError::IOError)?;
+
+    file_parser::validate_file(&mut file_parser::Stream::new(&buf))
+}
 
-    let game = file_parser::parse_file(&mut file_parser::Stream::new(&buf))?;
-    Ok(game)
+/// Like `read_game`, but for a file already in memory, such as in wasm.
+pub fn read_game_from_bytes(buf: &[u8]) -> SlpResult<(Game, Notes)> {
+    file_parser::parse_file(&mut file_parser::Stream::new(buf))
 }
 
 // TODO do not truncate metadata after notes
@@ -385,49 +1080,1022 @@ pub fn parse_game(game: &Path, port: Port) -> SlpResult<Box<[Action]>> {
 pub fn parse_buf(buf: &[u8], port: Port) -> SlpResult<Box<[Action]>> {
     let mut stream = file_parser::Stream::new(buf);
     let (game, _) = file_parser::parse_file(&mut stream)?;
+    Ok(parse_with_config(game.port_frames(port), &game.parse_config()).into_boxed_slice())
+}
 
-    let frames = match port {
-        Port::High => &game.high_port_frames,
-        Port::Low => &game.low_port_frames,
-    };
+/// Like `parse_buf`, but selects the player by port index (zero indexed) rather than `Port`.
+/// Works for any present port in doubles.
+/// Frames for other ports are never stored.
+pub fn parse_buf_port(buf: &[u8], port_idx: u8) -> SlpResult<Box<[Action]>> {
+    let mut stream = file_parser::Stream::new(buf);
+    let (game, _) = file_parser::parse_file_port(&mut stream, port_idx)?;
+    let frames = game.player_frames(port_idx).ok_or(SlpError::MissingPort(port_idx))?;
+    Ok(parse_with_config(frames, &game.parse_config()).into_boxed_slice())
+}
 
-    Ok(parse(frames).into_boxed_slice())
+/// Like `parse_buf`, but reads the game from a stream rather than a buffer.
+/// The raw file is never held in memory at once.
+pub fn parse_reader<R: std::io::Read>(mut reader: R, port: Port) -> SlpResult<Box<[Action]>> {
+    let (game, _) = file_parser::parse_file_reader(&mut reader)?;
+    Ok(parse_with_config(game.port_frames(port), &game.parse_config()).into_boxed_slice())
 }
 
-macro_rules! unwrap_or {
-    ($opt:expr, $else:expr) => {
-        match $opt {
-            Some(data) => data,
-            None => $else,
-        }
-    }
+/// Pairs each opponent action with the player's next action.
+/// The interactions borrow from the action slices, use `generate_interactions_owned` to store or send them.
+pub fn generate_interactions<'a>(player_actions: &'a [Action], opponent_actions: &'a [Action]) -> Box<[InteractionRef<'a>]> {
+    interactions_iter(player_actions, opponent_actions).collect()
 }
 
+/// Merges both players' actions into one timeline, ordered by `frame_start`.
+/// Ties put the low port first.
+/// Each list must be in order, as returned by `parse`.
+pub fn merge_actions<'a>(low: &'a [Action], high: &'a [Action]) -> Vec<(Port, &'a Action)> {
+    let mut merged = Vec::with_capacity(low.len() + high.len());
+    let mut low = low.iter().peekable();
+    let mut high = high.iter().peekable();
+
+    loop {
+        let port = match (low.peek(), high.peek()) {
+            (Some(l), Some(h)) => if h.frame_start < l.frame_start { Port::High } else { Port::Low },
+            (Some(_), None) => Port::Low,
+            (None, Some(_)) => Port::High,
+            (None, None) => break,
+        };
+
+        let action = match port {
+            Port::Low => low.next().unwrap(),
+            Port::High => high.next().unwrap(),
+        };
+        merged.push((port, action));
+    }
 
-pub fn generate_interactions<'a>(mut player_actions: &'a [Action], mut opponent_actions: &'a [Action]) -> Box<[InteractionRef<'a>]> {
-    let mut interactions = Vec::new();
+    merged
+}
 
-    let mut initiation;
-    let mut response;
-    (initiation, opponent_actions) = unwrap_or!(opponent_actions.split_first(), return interactions.into_boxed_slice());
-    (response, player_actions) = unwrap_or!(player_actions.split_first(), return interactions.into_boxed_slice());
+/// Like `generate_interactions`, but clones the actions so the interactions do not borrow the slices.
+pub fn generate_interactions_owned(player_actions: &[Action], opponent_actions: &[Action]) -> Vec<Interaction> {
+    interactions_iter(player_actions, opponent_actions)
+        .map(|i| i.to_interaction())
+        .collect()
+}
 
-    'outer: loop {
-        while response.frame_start <= initiation.frame_start {
-            (response, player_actions) = unwrap_or!(player_actions.split_first(), break 'outer);
-        }
+/// Like `generate_interactions`, but lazily yields each interaction without collecting them.
+pub fn interactions_iter<'a>(player_actions: &'a [Action], opponent_actions: &'a [Action]) -> InteractionIter<'a> {
+    let mut iter = InteractionIter { initiation: None, response: None, player_actions, opponent_actions };
+    if let (Some((initiation, opponent_actions)), Some((response, player_actions))) 
+        = (opponent_actions.split_first(), player_actions.split_first()) 
+    {
+        iter = InteractionIter { 
+            initiation: Some(initiation), 
+            response: Some(response), 
+            player_actions, 
+            opponent_actions,
+        };
+    }
+    iter
+}
+
+pub struct InteractionIter<'a> {
+    initiation: Option<&'a Action>,
+    response: Option<&'a Action>,
+    player_actions: &'a [Action],
+    opponent_actions: &'a [Action],
+}
+
+impl<'a> Iterator for InteractionIter<'a> {
+    type Item = InteractionRef<'a>;
+
+    fn next(&mut self) -> Option<InteractionRef<'a>> {
+        let initiation = self.initiation?;
+        let mut response = self.response?;
 
-        interactions.push(InteractionRef { 
+        while response.frame_start <= initiation.frame_start {
+            match self.player_actions.split_first() {
+                Some((next, rest)) => (response, self.player_actions) = (next, rest),
+                None => {
+                    self.response = None;
+                    return None;
+                }
+            }
+        }
+        self.response = Some(response);
+
+        let interaction = InteractionRef { 
             player_response: response,
             opponent_initiation: initiation,
-        });
+        };
 
+        let mut initiation = initiation;
         while initiation.frame_start <= response.frame_start {
-            (initiation, opponent_actions) = unwrap_or!(opponent_actions.split_first(), break 'outer);
+            match self.opponent_actions.split_first() {
+                Some((next, rest)) => (initiation, self.opponent_actions) = (next, rest),
+                None => {
+                    self.initiation = None;
+                    return Some(interaction);
+                }
+            }
+        }
+        self.initiation = Some(initiation);
+
+        Some(interaction)
+    }
+}
+
+pub const PUNISH_RESET_FRAMES: usize = 45;
+
+/// Groups the defender's hitstun into punishes, without moves, damage, or kills.
+fn group_punishes(defender: &[Action]) -> Vec<Punish> {
+    let mut punishes: Vec<Punish> = Vec::new();
+    for hitstun in defender.iter().filter(|a| a.action_taken == HighLevelAction::Hitstun) {
+        match punishes.last_mut() {
+            Some(p) if hitstun.frame_start <= p.frame_end + PUNISH_RESET_FRAMES => {
+                p.frame_end = hitstun.frame_end;
+            }
+            _ => punishes.push(Punish {
+                frame_start: hitstun.frame_start,
+                frame_end: hitstun.frame_end,
+                moves: 0,
+                damage: 0.0,
+                killed: false,
+            }),
+        }
+    }
+
+    punishes
+}
+
+/// Groups the opponent's hitstun into punishes by the player.
+/// A punish ends once the opponent goes `PUNISH_RESET_FRAMES` frames without being hit.
+pub fn detect_punishes(player: &[Action], opponent: &[Action], opponent_frames: &[Frame]) -> Vec<Punish> {
+    let mut punishes = group_punishes(opponent);
+
+    let deaths = deaths(opponent_frames);
+    let percent_at = |i: usize| opponent_frames.get(i).map(|f| f.percent);
+
+    for p in punishes.iter_mut() {
+        p.moves = player.iter()
+            .filter(|a| a.action_taken.is_attack())
+            .filter(|a| a.frame_end >= p.frame_start && a.frame_start < p.frame_end)
+            .count() as u32;
+
+        // percent resets on death, so use the last frame in hitstun
+        let start_percent = p.frame_start.checked_sub(1).and_then(percent_at);
+        let end_percent = p.frame_end.checked_sub(1).and_then(percent_at);
+        if let (Some(start), Some(end)) = (start_percent, end_percent) {
+            p.damage = (end - start).max(0.0);
+        }
+
+        p.killed = deaths.iter().any(|&d| d >= p.frame_start && d <= p.frame_end + PUNISH_RESET_FRAMES);
+    }
+
+    punishes
+}
+
+/// The `top_n` punishes dealing the most damage, highest first. Ties keep game order.
+pub fn best_punishes(player: &[Action], opponent: &[Action], opponent_frames: &[Frame], top_n: usize) -> Vec<Punish> {
+    let mut punishes = detect_punishes(player, opponent, opponent_frames);
+    punishes.sort_by(|a, b| b.damage.total_cmp(&a.damage));
+    punishes.truncate(top_n);
+    punishes
+}
+
+/// Damage dealt to the opponent during each action, in the same order as `player`.
+/// Multi-hit moves sum every hit. Percent lost on death is ignored.
+pub fn action_damage(player: &[Action], opponent_frames: &[Frame]) -> Vec<f32> {
+    player.iter()
+        .map(|a| {
+            let end = a.frame_end.min(opponent_frames.len().saturating_sub(1));
+            if a.frame_start >= end { return 0.0 }
+
+            opponent_frames[a.frame_start..=end]
+                .windows(2)
+                .map(|w| (w[1].percent - w[0].percent).max(0.0))
+                .sum()
+        })
+        .collect()
+}
+
+/// Punishes started by the player from neutral.
+/// Punishes started while the opponent was punishing the player are counter attacks, not neutral wins.
+/// Hits continuing a punish are part of that punish, so are not counted.
+pub fn neutral_wins(player: &[Action], opponent: &[Action]) -> u32 {
+    let player_punishes = group_punishes(opponent);
+    let opponent_punishes = group_punishes(player);
+
+    player_punishes.iter()
+        .filter(|p| !opponent_punishes.iter().any(|o| {
+            o.frame_start <= p.frame_start && p.frame_start <= o.frame_end + PUNISH_RESET_FRAMES
+        }))
+        .count() as u32
+}
+
+/// Openings, damage, and kills from the player's punishes on the opponent.
+pub fn conversion_stats(player: &[Action], opponent: &[Action], opponent_frames: &[Frame]) -> ConversionStats {
+    let punishes = detect_punishes(player, opponent, opponent_frames);
+
+    let openings = punishes.len() as u32;
+    let kills = punishes.iter().filter(|p| p.killed).count() as u32;
+    let total_damage = punishes.iter().map(|p| p.damage).sum();
+
+    ConversionStats {
+        openings,
+        neutral_wins: neutral_wins(player, opponent),
+        kills,
+        total_damage,
+        damage_per_opening: (openings != 0).then(|| total_damage / openings as f32),
+        openings_per_kill: (kills != 0).then(|| openings as f32 / kills as f32),
+    }
+}
+
+/// Fraction of landed aerials that were l-cancelled.
+/// Returns None if no aerials were landed.
+pub fn l_cancel_rate(actions: &[Action]) -> Option<f32> {
+    let mut landed = 0u32;
+    let mut l_cancelled = 0u32;
+
+    for l in actions.iter().filter_map(|a| a.l_cancelled) {
+        landed += 1;
+        if l { l_cancelled += 1; }
+    }
+
+    if landed == 0 { return None }
+    Some(l_cancelled as f32 / landed as f32)
+}
+
+const FRAMES_PER_MINUTE: f32 = 3600.0;
+
+/// Deliberate actions per minute. Waiting and hitstun are not counted.
+///
+/// `total_frames` should be the number of in-game frames, after the Ready/Go countdown.
+pub fn actions_per_minute(actions: &[Action], total_frames: usize) -> f32 {
+    if total_frames == 0 { return 0.0 }
+
+    let count = actions.iter()
+        .filter(|a| !matches!(
+            a.action_taken,
+            HighLevelAction::GroundWait | HighLevelAction::AirWait 
+                | HighLevelAction::LedgeWait | HighLevelAction::Hitstun
+        ))
+        .count();
+
+    count as f32 * FRAMES_PER_MINUTE / total_frames as f32
+}
+
+/// Rough inputs per minute.
+/// Counts button presses and the control stick leaving the deadzone.
+///
+/// `frames` should only contain in-game frames, after the Ready/Go countdown.
+pub fn inputs_per_minute(frames: &[Frame]) -> f32 {
+    if frames.is_empty() { return 0.0 }
+
+    let in_deadzone = |f: &Frame| {
+        f.left_stick_coords[0].abs() < STICK_DEADZONE && f.left_stick_coords[1].abs() < STICK_DEADZONE
+    };
+
+    let mut count = 0u32;
+    for w in frames.windows(2) {
+        let pressed = w[1].buttons_mask & !w[0].buttons_mask;
+        count += pressed.count_ones();
+        if in_deadzone(&w[0]) && !in_deadzone(&w[1]) { count += 1; }
+    }
+
+    count as f32 * FRAMES_PER_MINUTE / frames.len() as f32
+}
+
+/// Most frames between one action ending and the next starting for the `coalesce_*` functions to merge them.
+pub const COALESCE_MAX_GAP: usize = 3;
+
+/// Whether `actions[i]` starts within `COALESCE_MAX_GAP` frames of the previous action. False if out of bounds.
+fn follows_closely(actions: &[Action], i: usize) -> bool {
+    i < actions.len() && actions[i].frame_start <= actions[i-1].frame_end + COALESCE_MAX_GAP
+}
+
+/// Merges runs of alternating dashes into a single `DashDance` action.
+/// Dashes separated by more than `COALESCE_MAX_GAP` frames are not merged.
+pub fn coalesce_dash_dances(actions: &[Action]) -> Vec<Action> {
+    fn dash_dir(action: &Action) -> Option<Direction> {
+        match action.action_taken {
+            HighLevelAction::DashLeft => Some(Direction::Left),
+            HighLevelAction::DashRight => Some(Direction::Right),
+            _ => None,
+        }
+    }
+
+    let mut coalesced = Vec::with_capacity(actions.len());
+    let mut i = 0;
+    while i < actions.len() {
+        let first = &actions[i];
+        let mut dir = match dash_dir(first) {
+            Some(dir) => dir,
+            None => {
+                coalesced.push(first.clone());
+                i += 1;
+                continue;
+            }
+        };
+
+        let mut end = i + 1;
+        while let Some(next) = actions.get(end) {
+            match dash_dir(next) {
+                Some(next_dir) if next_dir != dir && follows_closely(actions, end) => {
+                    dir = next_dir;
+                    end += 1;
+                }
+                _ => break,
+            }
+        }
+
+        let count = end - i;
+        if count == 1 {
+            coalesced.push(first.clone());
+        } else {
+            coalesced.push(Action {
+                action_taken: HighLevelAction::DashDance { count: count.min(u8::MAX as usize) as u8 },
+                frame_end: actions[end-1].frame_end,
+                ..first.clone()
+            });
+        }
+        i = end;
+    }
+
+    coalesced
+}
+
+/// Merges Fox and Falco shine sequences into single actions.
+/// Jump cancelled shines directly after a shine become a `Multishine`,
+/// and a lone shine directly into a wavedash becomes a `Waveshine`.
+pub fn coalesce_shines(actions: &[Action]) -> Vec<Action> {
+    fn is_shine(action: &Action, jump_cancelled: bool) -> bool {
+        use SpecialHighLevelAction as SHLA;
+        match action.action_taken {
+            HighLevelAction::Special(SHLA::Fox(a)) => a == HighLevelActionFox::JumpCancelReflector 
+                || (!jump_cancelled && a == HighLevelActionFox::Reflector),
+            HighLevelAction::Special(SHLA::Falco(a)) => a == HighLevelActionFalco::JumpCancelReflector 
+                || (!jump_cancelled && a == HighLevelActionFalco::Reflector),
+            _ => false,
+        }
+    }
+
+    let mut coalesced = Vec::with_capacity(actions.len());
+    let mut i = 0;
+    while i < actions.len() {
+        let first = &actions[i];
+        if !is_shine(first, false) {
+            coalesced.push(first.clone());
+            i += 1;
+            continue;
+        }
+
+        let mut end = i + 1;
+        while follows_closely(actions, end) && is_shine(&actions[end], true) {
+            end += 1;
+        }
+
+        let count = end - i;
+        if count > 1 {
+            coalesced.push(Action {
+                action_taken: HighLevelAction::Multishine { count: count.min(u8::MAX as usize) as u8 },
+                frame_end: actions[end-1].frame_end,
+                ..first.clone()
+            });
+        } else if follows_closely(actions, end) && actions[end].action_taken.is_wavedash() {
+            coalesced.push(Action {
+                action_taken: HighLevelAction::Waveshine,
+                frame_end: actions[end].frame_end,
+                ..first.clone()
+            });
+            end += 1;
+        } else {
+            coalesced.push(first.clone());
         }
+        i = end;
     }
 
-    interactions.into_boxed_slice()
+    coalesced
+}
+
+/// Merges a shorthop or fullhop followed by a double jump aerial into a single `DoubleJumpAerial`.
+/// The double jump aerial may be a `JumpAerial`, or an `AirJump` directly followed by an `Aerial`.
+/// The hop must lead into the double jump within the air courtesy, so hops ending in `AirWait` are not merged.
+pub fn coalesce_double_jump_aerials(actions: &[Action]) -> Vec<Action> {
+    let mut coalesced = Vec::with_capacity(actions.len());
+    let mut i = 0;
+    while i < actions.len() {
+        let first = &actions[i];
+        let first_jump = match first.action_taken {
+            HighLevelAction::Fullhop => Some(JumpType::Full),
+            HighLevelAction::Shorthop => Some(JumpType::Short),
+            _ => None,
+        };
+
+        let aerial = first_jump.filter(|_| follows_closely(actions, i + 1)).and_then(|_| match actions[i + 1].action_taken {
+            HighLevelAction::JumpAerial(attack) => Some((attack, i + 2)),
+            HighLevelAction::AirJump if follows_closely(actions, i + 2) => match actions[i + 2].action_taken {
+                HighLevelAction::Aerial(attack) => Some((attack, i + 3)),
+                _ => None,
+            },
+            _ => None,
+        });
+
+        match (first_jump, aerial) {
+            (Some(first_jump), Some((attack, end))) => {
+                coalesced.push(Action {
+                    action_taken: HighLevelAction::DoubleJumpAerial { first_jump, attack },
+                    frame_end: actions[end-1].frame_end,
+                    actionable_frame: actions[end-1].actionable_frame,
+                    l_cancelled: actions[end-1].l_cancelled,
+                    ..first.clone()
+                });
+                i = end;
+            }
+            _ => {
+                coalesced.push(first.clone());
+                i += 1;
+            }
+        }
+    }
+
+    coalesced
+}
+
+/// Average GALINT over all ledgedashes.
+/// Returns None if there are no ledgedashes.
+pub fn average_galint(actions: &[Action]) -> Option<f32> {
+    let mut count = 0u32;
+    let mut total = 0i32;
+
+    for galint in actions.iter().filter_map(|a| a.galint) {
+        count += 1;
+        total += galint as i32;
+    }
+
+    if count == 0 { return None }
+    Some(total as f32 / count as f32)
+}
+
+/// Ledge habits over a game.
+/// `frames` are the same player's frames, for counting ledge stalls. See `ledge_stalls`.
+pub fn ledge_option_stats(actions: &[Action], frames: &[Frame]) -> LedgeStats {
+    let mut stats = LedgeStats::default();
+
+    for a in actions {
+        match a.action_taken {
+            HighLevelAction::LedgeWait => stats.waits += 1,
+            HighLevelAction::LedgeDash => stats.ledgedashes += 1,
+            HighLevelAction::LedgeGetUp => stats.getups += 1,
+            HighLevelAction::LedgeRoll => stats.rolls += 1,
+            HighLevelAction::LedgeJump => stats.jumps += 1,
+            HighLevelAction::LedgeHop => stats.hops += 1,
+            HighLevelAction::LedgeAerial(_) => stats.aerials += 1,
+            HighLevelAction::LedgeAttack => stats.attacks += 1,
+            HighLevelAction::LedgeDrop => stats.drops += 1,
+            _ => (),
+        }
+    }
+
+    stats.stalls = ledge_stalls(frames).len() as u32;
+    stats.average_galint = average_galint(actions);
+    stats
+}
+
+/// Number of times each action was taken, indexed by `HighLevelAction::as_u16`.
+/// Special actions are encoded past the end of the array, so are skipped. Use `action_counts_sorted` to count them.
+pub fn action_counts(actions: &[Action]) -> [u32; HighLevelAction::VARIANT_COUNT as usize] {
+    let mut counts = [0; HighLevelAction::VARIANT_COUNT as usize];
+    for a in actions {
+        if let HighLevelAction::Special(_) = a.action_taken { continue }
+        counts[a.action_taken.as_u16() as usize] += 1;
+    }
+    counts
+}
+
+/// Number of times each action was taken, most frequent first.
+/// Includes special actions.
+pub fn action_counts_sorted(actions: &[Action]) -> Vec<(HighLevelAction, u32)> {
+    let mut counts: Vec<(HighLevelAction, u32)> = Vec::new();
+    for a in actions {
+        match counts.iter_mut().find(|(hla, _)| *hla == a.action_taken) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((a.action_taken, 1)),
+        }
+    }
+
+    counts.sort_by_key(|&(_, n)| std::cmp::Reverse(n));
+    counts
+}
+
+/// Every item in the game, keyed by spawn id rather than slot, in order of spawning.
+pub fn item_lifetimes(game: &Game) -> Vec<ItemLife> {
+    let mut lives: Vec<ItemLife> = Vec::new();
+    let mut idx_by_spawn_id = std::collections::HashMap::new();
+
+    let frame_count = game.item_idx.len().saturating_sub(1);
+    for frame in 0..frame_count {
+        for item in game.items_on_frame(frame) {
+            let i = *idx_by_spawn_id.entry(item.spawn_id).or_insert_with(|| {
+                lives.push(ItemLife {
+                    spawn_id: item.spawn_id,
+                    type_id: item.type_id,
+                    owner: item.owner,
+                    spawn_frame: frame,
+                    despawn_frame: frame,
+                    trajectory: Vec::new(),
+                });
+                lives.len() - 1
+            });
+
+            let life = &mut lives[i];
+            if life.owner.is_none() { life.owner = item.owner }
+            life.despawn_frame = frame + 1;
+            life.trajectory.push(item.position);
+        }
+    }
+
+    lives
+}
+
+pub const FOX_LASER_ITEM_ID: u16 = 0x36;
+pub const FALCO_LASER_ITEM_ID: u16 = 0x37;
+
+/// Distance from the player within which an item counts as reflected by a shine.
+pub const REFLECT_RADIUS: f32 = 15.0;
+
+/// Laser shots and shine reflects for a Fox or Falco. Empty for other characters.
+pub fn projectile_events(game: &Game, port: Port) -> Vec<ProjectileEvent> {
+    let frames = game.port_frames(port);
+    let mut events = Vec::new();
+    let mut seen_lasers = std::collections::HashSet::new();
+
+    for (i, f) in frames.iter().enumerate() {
+        let (blaster, reflect) = match f.state.broad_state() {
+            BroadState::Special(SpecialBroadState::Fox(s)) => (
+                s == SpecialBroadStateFox::Blaster,
+                matches!(f.state, ActionState::Special(SpecialActionState::Fox(
+                    SpecialActionStateFox::ReflectorGroundReflect | SpecialActionStateFox::ReflectorAirReflect
+                ))),
+            ),
+            BroadState::Special(SpecialBroadState::Falco(s)) => (
+                s == SpecialBroadStateFalco::Blaster,
+                matches!(f.state, ActionState::Special(SpecialActionState::Falco(
+                    SpecialActionStateFalco::ReflectorGroundReflect | SpecialActionStateFalco::ReflectorAirReflect
+                ))),
+            ),
+            _ => continue,
+        };
+
+        if blaster {
+            for item in game.player_items(f.port_idx, i) {
+                let is_laser = matches!(item.type_id, FOX_LASER_ITEM_ID | FALCO_LASER_ITEM_ID);
+                if is_laser && seen_lasers.insert(item.spawn_id) {
+                    events.push(ProjectileEvent {
+                        kind: ProjectileEventKind::Laser,
+                        frame: i,
+                        spawn_id: item.spawn_id,
+                        position: item.position,
+                    });
+                }
+            }
+        }
+
+        let reflect_start = reflect && (i == 0 || frames[i-1].state != f.state);
+        if reflect_start {
+            let nearest = game.items_on_frame(i).iter()
+                .filter(|item| item.owner != Some(f.port_idx))
+                .map(|item| (item, item.position.distance(f.position)))
+                .filter(|&(_, d)| d <= REFLECT_RADIUS)
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+
+            if let Some((item, _)) = nearest {
+                events.push(ProjectileEvent {
+                    kind: ProjectileEventKind::Reflect,
+                    frame: i,
+                    spawn_id: item.spawn_id,
+                    position: item.position,
+                });
+            }
+        }
+    }
+
+    events
+}
+
+/// Frame indices where the character changed, from a Sheik/Zelda transformation.
+pub fn transformations(frames: &[Frame]) -> Vec<usize> {
+    frames.windows(2)
+        .enumerate()
+        .filter(|(_, w)| w[1].character != w[0].character)
+        .map(|(i, _)| i + 1)
+        .collect()
+}
+
+/// Frame indices where Pokemon Stadium changed form, with the new form.
+/// Empty on other stages. Transformations before the first frame are placed on frame 0.
+pub fn ps_transformations(game: &Game) -> Vec<(usize, StadiumTransformation)> {
+    match game.stage_info {
+        Some(StageInfo::Stadium(ref transformations)) => transformations.events.iter()
+            .map(|&(frame, t)| ((frame - game.first_frame).max(0) as usize, t))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Number of frames spent in each state.
+/// Character specific states above 340 are counted separately for each character's state.
+pub fn state_histogram(frames: &[Frame]) -> std::collections::HashMap<ActionState, u32> {
+    let mut histogram = std::collections::HashMap::new();
+    for f in frames {
+        *histogram.entry(f.state).or_insert(0) += 1;
+    }
+    histogram
+}
+
+/// Frame indices where nana desynced from popo.
+/// Nana follows popo a few frames behind, so a desync starts once their states
+/// have differed for `IC_DESYNC_FRAMES` frames in a row.
+pub fn ic_desyncs(frames: &[Frame]) -> Vec<usize> {
+    const IC_DESYNC_FRAMES: usize = 20;
+
+    let mut desyncs = Vec::new();
+    let mut differing = 0;
+    for (i, f) in frames.iter().enumerate() {
+        match f.nana {
+            Some(nana) if nana.state_num != f.state_num => differing += 1,
+            _ => differing = 0,
+        }
+
+        if differing == IC_DESYNC_FRAMES { desyncs.push(i + 1 - IC_DESYNC_FRAMES) }
+    }
+
+    desyncs
+}
+
+/// Frame indices where the shield broke, entering ShieldBreakFly or ShieldBreakFall.
+pub fn shield_breaks(frames: &[Frame]) -> Vec<usize> {
+    use StandardActionState::{ShieldBreakFly, ShieldBreakFall};
+
+    let is_break = |f: &Frame| matches!(f.state, ActionState::Standard(ShieldBreakFly | ShieldBreakFall));
+
+    frames.iter()
+        .enumerate()
+        .filter(|&(i, f)| is_break(f) && (i == 0 || !is_break(&frames[i-1])))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Frames spent grounded on the main stage, on each platform, on the ledge, and airborne.
+/// Fountain of Dreams side platforms move, so they are matched by nearest height only.
+pub fn surface_occupancy(frames: &[Frame], stage: Stage) -> SurfaceStats {
+    let mut stats = SurfaceStats {
+        platforms: vec![0; stage.platforms().len()],
+        ..SurfaceStats::default()
+    };
+
+    for f in frames {
+        let on_ledge = matches!(f.state.broad_state(), 
+            BroadState::Standard(StandardBroadState::Ledge | StandardBroadState::LedgeAction));
+
+        if on_ledge {
+            stats.ledge += 1;
+        } else if f.is_airborne {
+            stats.airborne += 1;
+        } else {
+            match stage.platform_at(f.position) {
+                Some(i) => stats.platforms[i] += 1,
+                None => stats.main_stage += 1,
+            }
+        }
+    }
+
+    stats
+}
+
+/// Jab resets on the defender: a hit while lying down, into a forced getup.
+pub fn jab_resets(defender_frames: &[Frame]) -> Vec<JabReset> {
+    use StandardActionState::*;
+
+    let is_down_damage = |f: &Frame| matches!(f.state, ActionState::Standard(DownDamageU | DownDamageD));
+    let is_getup = |f: &Frame| matches!(f.state, ActionState::Standard(DownStandU | DownStandD));
+    let hit_at = |i: usize| i > 0 && defender_frames[i].percent > defender_frames[i-1].percent;
+
+    let mut resets = Vec::new();
+    for (i, f) in defender_frames.iter().enumerate() {
+        if !is_down_damage(f) || (i > 0 && is_down_damage(&defender_frames[i-1])) { continue }
+
+        let Some(n) = defender_frames[i..].iter().position(|f| !is_down_damage(f)) else { break };
+        let getup_frame = i + n;
+        if !is_getup(&defender_frames[getup_frame]) { continue }
+
+        let followup_end = (getup_frame + PUNISH_RESET_FRAMES).min(defender_frames.len());
+        let followup_frame = (getup_frame..followup_end).find(|&j| hit_at(j));
+
+        resets.push(JabReset { frame: i, getup_frame, followup_frame });
+    }
+
+    resets
+}
+
+/// Sequences of two or more ledge grabs without landing or losing a stock in between.
+pub fn ledge_stalls(frames: &[Frame]) -> Vec<LedgeStall> {
+    let mut stalls = Vec::new();
+    let mut stall: Option<LedgeStall> = None;
+
+    let on_ledge = |f: &Frame| f.state.broad_state() == BroadState::Standard(StandardBroadState::Ledge);
+
+    for (i, f) in frames.iter().enumerate() {
+        let prev = i.checked_sub(1).map(|p| &frames[p]);
+
+        let landed = !f.is_airborne && !matches!(f.state.broad_state(), 
+            BroadState::Standard(StandardBroadState::Ledge | StandardBroadState::LedgeAction));
+        let died = prev.is_some_and(|p| f.stock_count < p.stock_count);
+        if landed || died {
+            if let Some(st) = stall.take() {
+                if st.grabs > 1 { stalls.push(st) }
+            }
+        }
+
+        let grabbed = f.state == ActionState::Standard(StandardActionState::CliffCatch)
+            && prev.is_none_or(|p| p.state != f.state);
+        if grabbed {
+            let st = stall.get_or_insert(LedgeStall { frame_start: i, frame_end: i, grabs: 0 });
+            st.grabs += 1;
+        }
+
+        if on_ledge(f) {
+            if let Some(ref mut st) = stall { st.frame_end = i + 1 }
+        }
+    }
+
+    if let Some(st) = stall {
+        if st.grabs > 1 { stalls.push(st) }
+    }
+
+    stalls
+}
+
+/// Segments where the player is off-stage, starting once they are airborne past the ledge.
+/// A segment ends when they grab ledge, land, or die.
+/// Empty for stages without known ledges.
+pub fn recovery_segments(frames: &[Frame], stage: Stage) -> Vec<RecoverySegment> {
+    let ledge_x = match stage.ledges() {
+        Some([_, right]) => right.x,
+        None => return Vec::new(),
+    };
+
+    let is_dead = |f: &Frame| matches!(f.state, ActionState::Standard(s) if s <= StandardActionState::DeadUpFallHitCameraIce);
+
+    let mut segments = Vec::new();
+    let mut segment: Option<RecoverySegment> = None;
+
+    for (i, f) in frames.iter().enumerate() {
+        let prev = i.checked_sub(1).map(|p| &frames[p]);
+
+        let seg = match segment {
+            Some(ref mut seg) => seg,
+            None => {
+                if f.is_airborne && f.position.x.abs() > ledge_x && !is_dead(f) {
+                    segment = Some(RecoverySegment {
+                        frame_start: i,
+                        frame_end: i,
+                        furthest_x: f.position.x,
+                        min_y: f.position.y,
+                        jumps: 0,
+                        specials: 0,
+                        wall_techs: 0,
+                        outcome: RecoveryOutcome::Unfinished,
+                    });
+                }
+                continue;
+            }
+        };
+
+        let outcome = if is_dead(f) || prev.is_some_and(|p| f.stock_count < p.stock_count) {
+            Some(RecoveryOutcome::Died)
+        } else if f.state.broad_state() == BroadState::Standard(StandardBroadState::Ledge) {
+            Some(RecoveryOutcome::Ledge)
+        } else if !f.is_airborne {
+            Some(RecoveryOutcome::Landed)
+        } else {
+            None
+        };
+
+        if let Some(outcome) = outcome {
+            seg.frame_end = i;
+            seg.outcome = outcome;
+            segments.push(*seg);
+            segment = None;
+            continue;
+        }
+
+        if f.position.x.abs() > seg.furthest_x.abs() { seg.furthest_x = f.position.x }
+        seg.min_y = seg.min_y.min(f.position.y);
+
+        let entered = |st: BroadState| f.state.broad_state() == st && prev.is_some_and(|p| p.state.broad_state() != st);
+        if entered(BroadState::Standard(StandardBroadState::AirJump)) { seg.jumps += 1 }
+        let wall_tech = |st: ActionState| matches!(st, ActionState::Standard(
+            StandardActionState::PassiveWall | StandardActionState::PassiveWallJump
+        ));
+        if wall_tech(f.state) && prev.is_some_and(|p| !wall_tech(p.state)) { seg.wall_techs += 1 }
+        if matches!(f.state, ActionState::Special(_)) && prev.is_some_and(|p| p.state.broad_state() != f.state.broad_state()) {
+            seg.specials += 1;
+        }
+    }
+
+    if let Some(mut seg) = segment {
+        seg.frame_end = frames.len();
+        segments.push(seg);
+    }
+
+    segments
+}
+
+/// Frame indices where the stock count decremented.
+pub fn deaths(frames: &[Frame]) -> Vec<usize> {
+    frames.windows(2)
+        .enumerate()
+        .filter(|(_, w)| w[1].stock_count < w[0].stock_count)
+        .map(|(i, _)| i + 1)
+        .collect()
+}
+
+/// An attack that ended this many frames before the victim took damage may still have landed it,
+/// as the attack's last active frame can come after the attacker is actionable again.
+const KILL_HIT_WINDOW: usize = 3;
+
+/// For each stock the victim lost to a hit, the death frame index, the killer's attack that landed last,
+/// and the victim's percent before dying.
+/// The attack must be in progress when the victim last took damage, or have ended within a few frames of it.
+/// Stocks lost without being hit, such as self destructs, or to damage no attack accounts for, such as items, are skipped.
+pub fn kill_moves(killer_actions: &[Action], victim_frames: &[Frame]) -> Vec<(usize, HighLevelAction, f32)> {
+    let mut kills = Vec::new();
+    let mut stock_start = 0;
+
+    for death in deaths(victim_frames) {
+        let last_hit = (stock_start.max(1)..death).rev()
+            .find(|&i| victim_frames[i].percent > victim_frames[i-1].percent);
+        stock_start = death;
+
+        let Some(hit) = last_hit else { continue };
+        let started = &killer_actions[..killer_actions.partition_point(|a| a.frame_start <= hit)];
+        let attacks = || started.iter().rev().filter(|a| a.action_taken.is_attack());
+        let killer = attacks().find(|a| hit < a.frame_end)
+            .or_else(|| attacks().find(|a| hit <= a.frame_end + KILL_HIT_WINDOW));
+
+        if let Some(killer) = killer {
+            kills.push((death, killer.action_taken, victim_frames[death - 1].percent));
+        }
+    }
+
+    kills
+}
+
+/// The action being performed on a frame index. None if the frame is between actions.
+/// Actions must be in order, as returned by `parse`.
+pub fn action_at_frame(actions: &[Action], frame: usize) -> Option<&Action> {
+    let i = actions.partition_point(|a| a.frame_start <= frame).checked_sub(1)?;
+    let action = &actions[i];
+    if frame < action.frame_end { Some(action) } else { None }
+}
+
+/// Maps each frame index to the index of the action being performed.
+/// Frames between actions, such as skipped inactionable frames, map to None.
+pub fn action_timeline(actions: &[Action], total_frames: usize) -> Vec<Option<usize>> {
+    let mut timeline = vec![None; total_frames];
+    for (i, a) in actions.iter().enumerate() {
+        let end = a.frame_end.min(total_frames);
+        if a.frame_start >= end { continue }
+        timeline[a.frame_start..end].fill(Some(i));
+    }
+    timeline
+}
+
+/// Frame data as parallel columns, one entry per frame. See `frames_to_columns`.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameColumns {
+    pub state: Vec<u16>, // u16, `Frame::state_num`
+    pub position_x: Vec<f32>, // f32
+    pub position_y: Vec<f32>, // f32
+    pub velocity_x: Vec<f32>, // f32, self induced velocity only
+    pub velocity_y: Vec<f32>, // f32, self induced velocity only
+    pub facing: Vec<i8>, // i8, -1 for left and 1 for right
+    pub anim_frame: Vec<f32>, // f32
+    pub percent: Vec<f32>, // f32
+    pub stock_count: Vec<u8>, // u8
+}
+
+impl FrameColumns {
+    pub fn len(&self) -> usize {
+        self.state.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.state.is_empty()
+    }
+
+    /// Every column concatenated in field order, each value little endian.
+    /// Column `i` of `len` values starts after the byte lengths of the previous columns,
+    /// so it can be viewed with e.g. `numpy.frombuffer(buf, dtype='<f4', count=len, offset=...)`.
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.len() * 28);
+        buf.extend(self.state.iter().flat_map(|n| n.to_le_bytes()));
+        buf.extend(self.position_x.iter().flat_map(|n| n.to_le_bytes()));
+        buf.extend(self.position_y.iter().flat_map(|n| n.to_le_bytes()));
+        buf.extend(self.velocity_x.iter().flat_map(|n| n.to_le_bytes()));
+        buf.extend(self.velocity_y.iter().flat_map(|n| n.to_le_bytes()));
+        buf.extend(self.facing.iter().flat_map(|n| n.to_le_bytes()));
+        buf.extend(self.anim_frame.iter().flat_map(|n| n.to_le_bytes()));
+        buf.extend(self.percent.iter().flat_map(|n| n.to_le_bytes()));
+        buf.extend(self.stock_count.iter().flat_map(|n| n.to_le_bytes()));
+        buf
+    }
+}
+
+/// Splits frames into numeric columns for dense array libraries.
+pub fn frames_to_columns(frames: &[Frame]) -> FrameColumns {
+    FrameColumns {
+        state: frames.iter().map(|f| f.state_num).collect(),
+        position_x: frames.iter().map(|f| f.position.x).collect(),
+        position_y: frames.iter().map(|f| f.position.y).collect(),
+        velocity_x: frames.iter().map(|f| f.velocity.x).collect(),
+        velocity_y: frames.iter().map(|f| f.velocity.y).collect(),
+        facing: frames.iter().map(|f| f.direction.sign() as i8).collect(),
+        anim_frame: frames.iter().map(|f| f.anim_frame).collect(),
+        percent: frames.iter().map(|f| f.percent).collect(),
+        stock_count: frames.iter().map(|f| f.stock_count).collect(),
+    }
+}
+
+/// Writes actions as CSV, with a header row.
+/// States and actions are written with their `Display` impls.
+pub fn write_actions_csv<W: std::io::Write>(mut w: W, actions: &[Action]) -> std::io::Result<()> {
+    fn csv_field(s: String) -> String {
+        if s.contains([',', '"', '\n']) {
+            format!("\"{}\"", s.replace('"', "\"\""))
+        } else {
+            s
+        }
+    }
+
+    writeln!(w, "frame_start,frame_end,start_state,action,init_x,init_y,init_vx,init_vy")?;
+    for a in actions {
+        writeln!(
+            w, "{},{},{},{},{},{},{},{}",
+            a.frame_start, a.frame_end,
+            csv_field(a.start_state.to_string()), csv_field(a.action_taken.to_string()),
+            a.initial_position.x, a.initial_position.y,
+            a.initial_velocity.x, a.initial_velocity.y,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Actions as a JSON array, with field names following slippi-js conventions.
+/// Frames are indices into the port's frames, not Slippi frame numbers.
+pub fn export_actions_json(actions: &[Action]) -> String {
+    use std::fmt::Write;
+
+    fn json_string(out: &mut String, s: &str) {
+        out.push('"');
+        for c in s.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                c if (c as u32) < 0x20 => { let _ = write!(out, "\\u{:04x}", c as u32); }
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+    }
+
+    // NaN and infinity are not valid json
+    fn json_f32(out: &mut String, n: f32) {
+        if n.is_finite() { let _ = write!(out, "{}", n); } else { out.push_str("null") }
+    }
+
+    let mut out = String::from("[");
+    for (i, a) in actions.iter().enumerate() {
+        if i != 0 { out.push(',') }
+        let _ = write!(out, "{{\"startFrame\":{},\"endFrame\":{},\"moveId\":{},\"move\":",
+            a.frame_start, a.frame_end, a.action_taken.as_u16());
+        json_string(&mut out, &a.action_taken.to_string());
+        out.push_str(",\"startState\":");
+        json_string(&mut out, &a.start_state.to_string());
+        out.push_str(",\"positionX\":");
+        json_f32(&mut out, a.initial_position.x);
+        out.push_str(",\"positionY\":");
+        json_f32(&mut out, a.initial_position.y);
+        out.push_str(",\"velocityX\":");
+        json_f32(&mut out, a.initial_velocity.x);
+        out.push_str(",\"velocityY\":");
+        json_f32(&mut out, a.initial_velocity.y);
+        out.push('}');
+    }
+    out.push(']');
+    out
 }
 
 use std::fmt;
@@ -437,6 +2105,27 @@ impl fmt::Display for Action {
     }
 }
 
+fn fmt_interaction(f: &mut fmt::Formatter<'_>, initiation: &Action, response: &Action) -> fmt::Result {
+    write!(
+        f, "{:15} ({}) vs {:15} ({}) [{:+}]",
+        initiation.action_taken, initiation.frame_start,
+        response.action_taken, response.frame_start,
+        response.frame_start as i64 - initiation.frame_start as i64,
+    )
+}
+
+impl<'a> fmt::Display for InteractionRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_interaction(f, self.opponent_initiation, self.player_response)
+    }
+}
+
+impl fmt::Display for Interaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_interaction(f, &self.opponent_initiation, &self.player_response)
+    }
+}
+
 impl fmt::Display for SlpError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", match self {
@@ -446,34 +2135,111 @@ impl fmt::Display for SlpError {
                 MIN_VERSION_MINOR,
             ),
             SlpError::InvalidFile => "Invalid file.".to_owned(),
-            SlpError::NotTwoPlayers => "File must be a two player match.".to_owned(),
+            SlpError::NotTwoPlayers => "File must have between two and four players.".to_owned(),
+            SlpError::MissingPort(n) => format!("No player in port {}.", n + 1),
             SlpError::UnimplementedCharacter(c) => format!(
                 "Character ({c}) is not yet implemented.",
             ),
+            SlpError::UnknownStage(n) => format!("Unknown stage id ({n})."),
+            SlpError::UnknownCharacter(n) => format!("Unknown character id ({n})."),
             SlpError::FileDoesNotExist => "File does not exist.".to_owned(),
             SlpError::IOError => "Error reading file.".to_owned(),
+            SlpError::CompressedFile => "File is compressed. Enable the flate2 feature to read it.".to_owned(),
         })
     }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vector {
     pub x: f32,
     pub y: f32,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+impl Vector {
+    pub const ZERO: Vector = Vector { x: 0.0, y: 0.0 };
+
+    pub const fn new(x: f32, y: f32) -> Self {
+        Vector { x, y }
+    }
+
+    pub fn magnitude(self) -> f32 {
+        self.x.hypot(self.y)
+    }
+
+    /// Degrees counterclockwise from the positive x axis, in -180..=180. 0 is right, 90 is up, -90 is down.
+    pub fn angle_degrees(self) -> f32 {
+        self.y.atan2(self.x).to_degrees()
+    }
+
+    pub fn distance(self, other: Vector) -> f32 {
+        (self - other).magnitude()
+    }
+
+    pub fn dot(self, other: Vector) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+}
+
+/// From stick coordinates.
+impl From<[f32; 2]> for Vector {
+    fn from([x, y]: [f32; 2]) -> Self {
+        Vector { x, y }
+    }
+}
+
+impl std::ops::Add for Vector {
+    type Output = Vector;
+    fn add(self, rhs: Vector) -> Vector { Vector { x: self.x + rhs.x, y: self.y + rhs.y } }
+}
+
+impl std::ops::Sub for Vector {
+    type Output = Vector;
+    fn sub(self, rhs: Vector) -> Vector { Vector { x: self.x - rhs.x, y: self.y - rhs.y } }
+}
+
+impl std::ops::Mul<f32> for Vector {
+    type Output = Vector;
+    fn mul(self, rhs: f32) -> Vector { Vector { x: self.x * rhs, y: self.y * rhs } }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Direction {
     Left,
     Right
 }
 
+impl Direction {
+    pub fn flip(self) -> Self {
+        match self {
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    /// -1.0 for left and 1.0 for right, matching the sign of x positions and velocities.
+    pub fn sign(self) -> f32 {
+        match self {
+            Direction::Left => -1.0,
+            Direction::Right => 1.0,
+        }
+    }
+
+    /// Left if negative, otherwise right. Zero and NaN are right.
+    pub fn from_sign(sign: f32) -> Self {
+        if sign < 0.0 { Direction::Left } else { Direction::Right }
+    }
+}
+
 // zero if invalid time
 #[derive(Copy, Clone, Debug, PartialOrd, Ord, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Time(u64);
 impl Time { pub const NULL: Time = Time(0); }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TimeFields {
     pub year: u16,
     pub month: u8,
@@ -509,3 +2275,309 @@ impl From<TimeFields> for Time {
         Time(time)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::*;
+
+    #[test]
+    fn merge_actions_orders_by_start() {
+        let low = [
+            action(HighLevelAction::GroundWait, 0, 10),
+            action(HighLevelAction::DashLeft, 10, 20),
+            action(HighLevelAction::AirWait, 20, 30),
+        ];
+        // overlaps both low actions, and starts on the same frame as the second
+        let high = [
+            action(HighLevelAction::DashRight, 5, 15),
+            action(HighLevelAction::AirWait, 10, 25),
+        ];
+
+        let merged: Vec<_> = merge_actions(&low, &high).into_iter()
+            .map(|(port, a)| (port, a.frame_start))
+            .collect();
+        assert_eq!(merged, [
+            (Port::Low, 0),
+            (Port::High, 5),
+            (Port::Low, 10),
+            (Port::High, 10),
+            (Port::Low, 20),
+        ]);
+    }
+
+    #[test]
+    fn action_counts_by_code() {
+        let actions = [
+            action(HighLevelAction::Hitstun, 0, 10),
+            action(HighLevelAction::Hitstun, 10, 20),
+            action(HighLevelAction::Walljump, 20, 30),
+            action(HighLevelAction::Special(SpecialHighLevelAction::Fox(HighLevelActionFox::Reflector)), 30, 40),
+        ];
+
+        let counts = action_counts(&actions);
+        assert_eq!(counts[63], 2);
+        assert_eq!(counts[64], 1);
+        assert_eq!(counts.iter().sum::<u32>(), 3);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn actions_json_round_trip() {
+        use StandardActionState::*;
+
+        let mut frames = frames(&[
+            (Wait, 5), (KneeBend, 3), (JumpF, 5), (AttackAirN, 20), (Fall, 10), (Landing, 4), (Wait, 10),
+        ]);
+        for f in frames.iter_mut().filter(|f| matches!(f.state, ActionState::Standard(JumpF | AttackAirN | Fall))) {
+            f.is_airborne = true;
+            f.velocity.y = 1.0;
+        }
+        let actions = parse(&frames);
+        assert!(actions.iter().any(|a| matches!(a.action_taken, HighLevelAction::ShorthopAerial(AirAttack::Nair))));
+
+        let json = serde_json::to_string(&actions).unwrap();
+        let parsed: Vec<Action> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, actions);
+    }
+
+    #[test]
+    fn actions_csv() {
+        let mut dash = action(HighLevelAction::DashDance { count: 3 }, 10, 40);
+        dash.initial_position = Vector::new(-12.5, 0.0);
+        dash.initial_velocity = Vector::new(1.5, 0.0);
+        let actions = [action(HighLevelAction::GroundWait, 0, 10), dash];
+
+        let mut csv = Vec::new();
+        write_actions_csv(&mut csv, &actions).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap(), concat!(
+            "frame_start,frame_end,start_state,action,init_x,init_y,init_vx,init_vy\n",
+            "0,10,Standing,Wait on ground,0,0,0,0\n",
+            "10,40,Standing,Dash dance (3),-12.5,0,1.5,0\n",
+        ));
+    }
+
+    #[test]
+    fn vector_math() {
+        let a = Vector::new(3.0, 4.0);
+        let b = Vector::new(1.0, -2.0);
+
+        assert_eq!(a.magnitude(), 5.0);
+        assert_eq!(a + b, Vector::new(4.0, 2.0));
+        assert_eq!(a - b, Vector::new(2.0, 6.0));
+        assert_eq!(a * 2.0, Vector::new(6.0, 8.0));
+        assert_eq!(a.dot(b), -5.0);
+        assert_eq!(a.distance(b), 40f32.sqrt());
+        assert_eq!(a.distance(a), 0.0);
+    }
+
+    #[test]
+    fn vector_angle_convention() {
+        assert_eq!(Vector::new(1.0, 0.0).angle_degrees(), 0.0);
+        assert_eq!(Vector::new(0.0, 1.0).angle_degrees(), 90.0);
+        assert_eq!(Vector::new(0.0, -1.0).angle_degrees(), -90.0);
+        assert_eq!(Vector::new(-1.0, 0.0).angle_degrees(), 180.0);
+
+        // stick held down and forwards, as for a wavedash
+        assert!((Vector::from([0.7, -0.7]).angle_degrees() + 45.0).abs() < 1e-4);
+        assert!((Vector::from([-0.7, -0.7]).angle_degrees() + 135.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn coalesce_shines_multishine() {
+        let fox = |a| HighLevelAction::Special(SpecialHighLevelAction::Fox(a));
+        let actions = [
+            action(HighLevelAction::GroundWait, 0, 10),
+            action(fox(HighLevelActionFox::Reflector), 10, 14),
+            action(fox(HighLevelActionFox::JumpCancelReflector), 14, 21),
+            action(fox(HighLevelActionFox::JumpCancelReflector), 22, 29),
+            // too late to be part of the multishine
+            action(fox(HighLevelActionFox::JumpCancelReflector), 40, 47),
+        ];
+
+        let coalesced = coalesce_shines(&actions);
+        let taken: Vec<_> = coalesced.iter().map(|a| a.action_taken).collect();
+        assert_eq!(taken, [
+            HighLevelAction::GroundWait,
+            HighLevelAction::Multishine { count: 3 },
+            fox(HighLevelActionFox::JumpCancelReflector),
+        ]);
+        assert_eq!((coalesced[1].frame_start, coalesced[1].frame_end), (10, 29));
+    }
+
+    #[test]
+    fn coalesce_shines_waveshine() {
+        let falco = |a| HighLevelAction::Special(SpecialHighLevelAction::Falco(a));
+        let actions = [
+            action(falco(HighLevelActionFalco::Reflector), 0, 5),
+            action(HighLevelAction::WavedashLeft, 5, 20),
+            action(falco(HighLevelActionFalco::Reflector), 30, 35),
+            action(HighLevelAction::GroundWait, 35, 40),
+        ];
+
+        let coalesced = coalesce_shines(&actions);
+        let taken: Vec<_> = coalesced.iter().map(|a| a.action_taken).collect();
+        assert_eq!(taken, [
+            HighLevelAction::Waveshine,
+            falco(HighLevelActionFalco::Reflector),
+            HighLevelAction::GroundWait,
+        ]);
+        assert_eq!((coalesced[0].frame_start, coalesced[0].frame_end), (0, 20));
+    }
+
+    #[test]
+    fn recovery_with_wall_tech() {
+        use StandardActionState::*;
+
+        let mut frames = frames(&[
+            (Wait, 5), (DamageFall, 10), (PassiveWall, 8), (Fall, 5), (JumpAerialF, 10), (CliffCatch, 5), (CliffWait, 10),
+        ]);
+        for (i, f) in frames.iter_mut().enumerate().skip(5) {
+            f.position = Vector::new(110.0 - i as f32, -20.0);
+            f.is_airborne = !matches!(f.state, ActionState::Standard(CliffCatch | CliffWait));
+        }
+
+        let segments = recovery_segments(&frames, Stage::FinalDestination);
+        assert_eq!(segments.len(), 1);
+        let seg = segments[0];
+        assert_eq!((seg.frame_start, seg.frame_end), (5, 38));
+        assert_eq!(seg.outcome, RecoveryOutcome::Ledge);
+        assert_eq!(seg.wall_techs, 1);
+        assert_eq!(seg.jumps, 1);
+    }
+
+    #[test]
+    fn port_mapping_ports_two_and_four() {
+        let f = frame(StandardActionState::Wait);
+        let bytes = ReplayBuilder::new()
+            .ports([1, 3])
+            .frame(-123, [&f, &f])
+            .game_end()
+            .last_frame(-123)
+            .build();
+        let info = read_info_from_bytes(&bytes).unwrap();
+
+        assert_eq!(info.port_for(Port::Low), 1);
+        assert_eq!(info.port_for(Port::High), 3);
+        assert_eq!(info.which_port(1), Some(Port::Low));
+        assert_eq!(info.which_port(3), Some(Port::High));
+        assert_eq!(info.which_port(0), None);
+        assert_eq!(info.which_port(2), None);
+
+        let (game, _) = read_game_from_bytes(&bytes).unwrap();
+        let ports: Vec<_> = game.players.iter().map(|p| p.port_idx).collect();
+        assert_eq!(ports, [info.port_for(Port::Low), info.port_for(Port::High)]);
+    }
+
+    #[test]
+    fn direction_helpers() {
+        for d in [Direction::Left, Direction::Right] {
+            assert_ne!(d.flip(), d);
+            assert_eq!(d.flip().flip(), d);
+            assert_eq!(d.flip().sign(), -d.sign());
+            assert_eq!(Direction::from_sign(d.sign()), d);
+        }
+
+        assert_eq!(Direction::Left.sign(), -1.0);
+        assert_eq!(Direction::from_sign(-0.5), Direction::Left);
+        assert_eq!(Direction::from_sign(2.0), Direction::Right);
+
+        // tie-break
+        assert_eq!(Direction::from_sign(0.0), Direction::Right);
+        assert_eq!(Direction::from_sign(-0.0), Direction::Right);
+        assert_eq!(Direction::from_sign(f32::NAN), Direction::Right);
+    }
+
+    #[test]
+    fn kill_moves_need_a_landing_attack() {
+        let mut victim = run(StandardActionState::Wait, 200);
+        for (i, f) in victim.iter_mut().enumerate() {
+            f.percent = match i {
+                0..20 => 0.0,
+                20..50 => 120.0,
+                50..120 => 0.0,
+                120..160 => 15.0, // from an item
+                160..190 => 0.0,
+                _ => 30.0,
+            };
+            f.stock_count = match i {
+                0..50 => 4,
+                50..160 => 3,
+                160..199 => 2,
+                _ => 1,
+            };
+        }
+
+        let killer = [
+            action(HighLevelAction::GroundAttack(GroundAttack::Fsmash), 10, 30),
+            action(HighLevelAction::GroundAttack(GroundAttack::Ftilt), 60, 80),
+            action(HighLevelAction::GroundWait, 80, 180),
+            // ended just before the damage registered
+            action(HighLevelAction::Aerial(AirAttack::Bair), 180, 189),
+        ];
+
+        assert_eq!(kill_moves(&killer, &victim), [
+            (50, HighLevelAction::GroundAttack(GroundAttack::Fsmash), 120.0),
+            (199, HighLevelAction::Aerial(AirAttack::Bair), 30.0),
+        ]);
+    }
+
+    #[test]
+    fn player_facing_opponent_bounds() {
+        let player = run(StandardActionState::Wait, 20);
+        let mut opponent = run(StandardActionState::Wait, 20);
+        for f in &mut opponent { f.position.x = -10.0 }
+
+        let interaction = Interaction {
+            opponent_initiation: action(HighLevelAction::DashLeft, 0, 10),
+            player_response: action(HighLevelAction::DashRight, 12, 20),
+        };
+        assert_eq!(interaction.player_facing_opponent(&player, &opponent), Some(false));
+        assert_eq!(interaction.player_facing_opponent(&player, &opponent[..12]), None);
+        assert_eq!(interaction.player_facing_opponent(&player[..5], &opponent), None);
+
+        let interaction = InteractionRef {
+            opponent_initiation: &interaction.opponent_initiation,
+            player_response: &interaction.player_response,
+        };
+        let mut player = player;
+        for f in &mut player { f.direction = Direction::Left }
+        assert_eq!(interaction.player_facing_opponent(&player, &opponent), Some(true));
+    }
+
+    #[test]
+    fn ledge_option_stats_counts() {
+        use StandardActionState::*;
+
+        let ledgedash = |start, galint| Action { galint, ..action(HighLevelAction::LedgeDash, start, start + 10) };
+        let actions = [
+            action(HighLevelAction::LedgeWait, 0, 10),
+            ledgedash(10, Some(4)),
+            action(HighLevelAction::LedgeWait, 20, 30),
+            ledgedash(30, Some(1)),
+            ledgedash(40, None),
+            action(HighLevelAction::LedgeRoll, 50, 60),
+            action(HighLevelAction::LedgeAerial(AirAttack::Bair), 60, 70),
+        ];
+
+        // drops from ledge and regrabs twice, then climbs up
+        let mut frames = frames(&[
+            (CliffCatch, 7), (CliffWait, 5), (Fall, 5), (CliffCatch, 7), (CliffWait, 5), (Fall, 5),
+            (CliffCatch, 7), (CliffClimbQuick, 30), (Wait, 10),
+        ]);
+        for f in &mut frames {
+            f.is_airborne = matches!(f.state, ActionState::Standard(Fall));
+        }
+
+        let stats = ledge_option_stats(&actions, &frames);
+        assert_eq!(stats.waits, 2);
+        assert_eq!(stats.ledgedashes, 3);
+        assert_eq!(stats.rolls, 1);
+        assert_eq!(stats.aerials, 1);
+        assert_eq!(stats.total(), 5);
+        assert_eq!(stats.stalls, 1);
+        assert_eq!(stats.average_galint, Some(2.5));
+
+        assert_eq!(ledge_option_stats(&[], &[]), LedgeStats::default());
+    }
+}
@@ -1,7 +1,7 @@
 mod special_states;
 pub use special_states::*;
 
-use crate::{SlpResult, SlpError, Character};
+use crate::{SlpResult, SlpError, Character, Direction};
 
 // It is very useful to abstract away special moves from standard moves, 
 // since they need to be parsed separatately and differently per character.
@@ -10,17 +10,20 @@ use crate::{SlpResult, SlpError, Character};
 // special, standard, and combined enums of HighLevelActions, BroadStates, and ActionStates.
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BroadState {
     Standard(StandardBroadState),
     Special(SpecialBroadState),
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StandardBroadState {
     Attack,
     Air,
     Airdodge,
     SpecialLanding, // from airdodge or special fall
+    SpecialFall, // helpless, after up-b or airdodge
     Ground,
     Walk,
     DashRun,
@@ -35,11 +38,15 @@ pub enum StandardBroadState {
     Grab,
     Roll,
     Spotdodge,
+    Knockdown, // missed tech, techs, and getups
+    Turn,
+    Throw,
 }
 
 /// Multi-frame actions.
 /// Must be derivable from a sequence of BroadStates.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum HighLevelAction {
     GroundAttack(GroundAttack),
@@ -80,10 +87,36 @@ pub enum HighLevelAction {
     Crouch,
     Hitstun,
     Walljump,
+    SpecialFall,
+    TechInPlace,
+    TechRoll(Direction),
+    TechWall,
+    TechCeiling,
+    MissedTech, // hit or still lying down when the action ends
+    MissedTechGetUp, // neutral getup, DownStandU/D
+    MissedTechAttack, // getup attack, DownAttackU/D
+    MissedTechRoll(Direction), // getup roll, DownFowardU/D or DownBackU/D. direction moved
+    DashDance { count: u8 }, // only produced by coalesce_dash_dances
+    Pivot,
+    ShieldDrop,
+    GrabWhiff,
+    Throw(ThrowDirection),
+    JabCancel, // jab interrupted by another ground action
+    Moonwalk(Direction), // direction moved, opposite to facing
+    GrabRelease, // grabbed opponent broke out
+    EmptyPivot, // pivot followed by waiting
+    PivotGrab, // connected grab directly out of a pivot, unless it led to a throw or release
+    Multishine { count: u8 }, // only produced by coalesce_shines
+    Waveshine, // only produced by coalesce_shines
+    Inactionable, // only produced with ParseConfig::emit_inactionable
+    TechWallJump, // jump out of a wall tech, PassiveWallJump
+    DoubleJumpAerial { first_jump: crate::JumpType, attack: AirAttack }, // only produced by coalesce_double_jump_aerials
+    ReverseAerialRush(AirAttack), // jump out of a run turnaround, attacking while facing away from the momentum
     Special(SpecialHighLevelAction),
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ActionState {
     Standard(StandardActionState),
     Special(SpecialActionState),
@@ -98,6 +131,168 @@ impl BroadState {
     }
 }
 
+impl BroadState {
+    /// Standard states first, then special states ordered by character then variant.
+    pub fn as_u16(self) -> u16 {
+        match self {
+            BroadState::Standard(s) => s.as_u8() as u16,
+            BroadState::Special(s) => StandardBroadState::VARIANT_COUNT as u16 + s.as_u16(),
+        }
+    }
+
+    pub fn from_u16(n: u16) -> Option<Self> {
+        match n.checked_sub(StandardBroadState::VARIANT_COUNT as u16) {
+            None => StandardBroadState::from_u8(n as u8).map(BroadState::Standard),
+            Some(n) => SpecialBroadState::from_u16(n).map(BroadState::Special),
+        }
+    }
+}
+
+impl StandardBroadState {
+    pub const MAX_VALUE: u8 = 21;
+    pub const VARIANT_COUNT: u8 = 22;
+
+    pub fn from_u8(n: u8) -> Option<Self> {
+        use StandardBroadState as SBS;
+        Some(match n {
+            0 => SBS::Attack,
+            1 => SBS::Air,
+            2 => SBS::Airdodge,
+            3 => SBS::SpecialLanding,
+            4 => SBS::SpecialFall,
+            5 => SBS::Ground,
+            6 => SBS::Walk,
+            7 => SBS::DashRun,
+            8 => SBS::Shield,
+            9 => SBS::Ledge,
+            10 => SBS::LedgeAction,
+            11 => SBS::Hitstun,
+            12 => SBS::GenericInactionable,
+            13 => SBS::JumpSquat,
+            14 => SBS::AirJump,
+            15 => SBS::Crouch,
+            16 => SBS::Grab,
+            17 => SBS::Roll,
+            18 => SBS::Spotdodge,
+            19 => SBS::Knockdown,
+            20 => SBS::Turn,
+            Self::MAX_VALUE => SBS::Throw,
+            Self::VARIANT_COUNT.. => return None,
+        })
+    }
+
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+}
+
+impl SpecialBroadState {
+    const COUNTS: [usize; 26] = [
+        SpecialBroadStateCaptainFalcon::VARIANT_COUNT,
+        SpecialBroadStateDonkeyKong::VARIANT_COUNT,
+        SpecialBroadStateFox::VARIANT_COUNT,
+        SpecialBroadStateMrGameAndWatch::VARIANT_COUNT,
+        SpecialBroadStateKirby::VARIANT_COUNT,
+        SpecialBroadStateBowser::VARIANT_COUNT,
+        SpecialBroadStateLink::VARIANT_COUNT,
+        SpecialBroadStateLuigi::VARIANT_COUNT,
+        SpecialBroadStateMario::VARIANT_COUNT,
+        SpecialBroadStateMarth::VARIANT_COUNT,
+        SpecialBroadStateMewtwo::VARIANT_COUNT,
+        SpecialBroadStateNess::VARIANT_COUNT,
+        SpecialBroadStatePeach::VARIANT_COUNT,
+        SpecialBroadStatePikachu::VARIANT_COUNT,
+        SpecialBroadStateIceClimbers::VARIANT_COUNT,
+        SpecialBroadStateJigglypuff::VARIANT_COUNT,
+        SpecialBroadStateSamus::VARIANT_COUNT,
+        SpecialBroadStateYoshi::VARIANT_COUNT,
+        SpecialBroadStateZelda::VARIANT_COUNT,
+        SpecialBroadStateSheik::VARIANT_COUNT,
+        SpecialBroadStateFalco::VARIANT_COUNT,
+        SpecialBroadStateYoungLink::VARIANT_COUNT,
+        SpecialBroadStateDrMario::VARIANT_COUNT,
+        SpecialBroadStateRoy::VARIANT_COUNT,
+        SpecialBroadStatePichu::VARIANT_COUNT,
+        SpecialBroadStateGanondorf::VARIANT_COUNT,
+    ];
+
+    const OFFSETS: [u16; 26] = {
+        let mut offsets = [0u16; 26];
+        let mut i = 1;
+        while i < 26 {
+            offsets[i] = offsets[i-1] + Self::COUNTS[i-1] as u16;
+            i += 1;
+        }
+        offsets
+    };
+
+    /// Unique across characters, ordered by character then variant.
+    pub fn as_u16(self) -> u16 {
+        match self {
+            SpecialBroadState::CaptainFalcon(s) => Self::OFFSETS[0] + s as u16,
+            SpecialBroadState::DonkeyKong(s) => Self::OFFSETS[1] + s as u16,
+            SpecialBroadState::Fox(s) => Self::OFFSETS[2] + s as u16,
+            SpecialBroadState::MrGameAndWatch(s) => Self::OFFSETS[3] + s as u16,
+            SpecialBroadState::Kirby(s) => Self::OFFSETS[4] + s as u16,
+            SpecialBroadState::Bowser(s) => Self::OFFSETS[5] + s as u16,
+            SpecialBroadState::Link(s) => Self::OFFSETS[6] + s as u16,
+            SpecialBroadState::Luigi(s) => Self::OFFSETS[7] + s as u16,
+            SpecialBroadState::Mario(s) => Self::OFFSETS[8] + s as u16,
+            SpecialBroadState::Marth(s) => Self::OFFSETS[9] + s as u16,
+            SpecialBroadState::Mewtwo(s) => Self::OFFSETS[10] + s as u16,
+            SpecialBroadState::Ness(s) => Self::OFFSETS[11] + s as u16,
+            SpecialBroadState::Peach(s) => Self::OFFSETS[12] + s as u16,
+            SpecialBroadState::Pikachu(s) => Self::OFFSETS[13] + s as u16,
+            SpecialBroadState::IceClimbers(s) => Self::OFFSETS[14] + s as u16,
+            SpecialBroadState::Jigglypuff(s) => Self::OFFSETS[15] + s as u16,
+            SpecialBroadState::Samus(s) => Self::OFFSETS[16] + s as u16,
+            SpecialBroadState::Yoshi(s) => Self::OFFSETS[17] + s as u16,
+            SpecialBroadState::Zelda(s) => Self::OFFSETS[18] + s as u16,
+            SpecialBroadState::Sheik(s) => Self::OFFSETS[19] + s as u16,
+            SpecialBroadState::Falco(s) => Self::OFFSETS[20] + s as u16,
+            SpecialBroadState::YoungLink(s) => Self::OFFSETS[21] + s as u16,
+            SpecialBroadState::DrMario(s) => Self::OFFSETS[22] + s as u16,
+            SpecialBroadState::Roy(s) => Self::OFFSETS[23] + s as u16,
+            SpecialBroadState::Pichu(s) => Self::OFFSETS[24] + s as u16,
+            SpecialBroadState::Ganondorf(s) => Self::OFFSETS[25] + s as u16,
+        }
+    }
+
+    pub fn from_u16(n: u16) -> Option<Self> {
+        let i = Self::OFFSETS.iter().rposition(|&o| o <= n)?;
+        let n = u8::try_from(n - Self::OFFSETS[i]).ok()?;
+        match i {
+            0  => SpecialBroadStateCaptainFalcon::from_u8(n).map(SpecialBroadState::CaptainFalcon),
+            1  => SpecialBroadStateDonkeyKong::from_u8(n).map(SpecialBroadState::DonkeyKong),
+            2  => SpecialBroadStateFox::from_u8(n).map(SpecialBroadState::Fox),
+            3  => SpecialBroadStateMrGameAndWatch::from_u8(n).map(SpecialBroadState::MrGameAndWatch),
+            4  => SpecialBroadStateKirby::from_u8(n).map(SpecialBroadState::Kirby),
+            5  => SpecialBroadStateBowser::from_u8(n).map(SpecialBroadState::Bowser),
+            6  => SpecialBroadStateLink::from_u8(n).map(SpecialBroadState::Link),
+            7  => SpecialBroadStateLuigi::from_u8(n).map(SpecialBroadState::Luigi),
+            8  => SpecialBroadStateMario::from_u8(n).map(SpecialBroadState::Mario),
+            9  => SpecialBroadStateMarth::from_u8(n).map(SpecialBroadState::Marth),
+            10 => SpecialBroadStateMewtwo::from_u8(n).map(SpecialBroadState::Mewtwo),
+            11 => SpecialBroadStateNess::from_u8(n).map(SpecialBroadState::Ness),
+            12 => SpecialBroadStatePeach::from_u8(n).map(SpecialBroadState::Peach),
+            13 => SpecialBroadStatePikachu::from_u8(n).map(SpecialBroadState::Pikachu),
+            14 => SpecialBroadStateIceClimbers::from_u8(n).map(SpecialBroadState::IceClimbers),
+            15 => SpecialBroadStateJigglypuff::from_u8(n).map(SpecialBroadState::Jigglypuff),
+            16 => SpecialBroadStateSamus::from_u8(n).map(SpecialBroadState::Samus),
+            17 => SpecialBroadStateYoshi::from_u8(n).map(SpecialBroadState::Yoshi),
+            18 => SpecialBroadStateZelda::from_u8(n).map(SpecialBroadState::Zelda),
+            19 => SpecialBroadStateSheik::from_u8(n).map(SpecialBroadState::Sheik),
+            20 => SpecialBroadStateFalco::from_u8(n).map(SpecialBroadState::Falco),
+            21 => SpecialBroadStateYoungLink::from_u8(n).map(SpecialBroadState::YoungLink),
+            22 => SpecialBroadStateDrMario::from_u8(n).map(SpecialBroadState::DrMario),
+            23 => SpecialBroadStateRoy::from_u8(n).map(SpecialBroadState::Roy),
+            24 => SpecialBroadStatePichu::from_u8(n).map(SpecialBroadState::Pichu),
+            25 => SpecialBroadStateGanondorf::from_u8(n).map(SpecialBroadState::Ganondorf),
+            _ => unreachable!(),
+        }
+    }
+}
+
 impl ActionState {
     pub fn broad_state(self) -> BroadState {
         match self {
@@ -122,6 +317,19 @@ impl ActionState {
         }
     }
 
+    /// None if the id is not a known state for the character.
+    pub fn try_from_u16(n: u16, character: Character) -> Option<Self> {
+        Self::from_u16(n, character).ok()
+    }
+
+    /// Special states are never actionable.
+    pub fn is_actionable(self) -> bool {
+        match self {
+            ActionState::Standard(st) => st.is_actionable(),
+            ActionState::Special(_) => false,
+        }
+    }
+
     pub fn internal_name(self) -> &'static str {
         match self {
             ActionState::Standard(st) => st.internal_name(),
@@ -138,12 +346,14 @@ impl ActionState {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AttackType {
     GroundAttack(GroundAttack),
     AirAttack(AirAttack),
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LedgeAction {
     Attack,
     Jump,
@@ -152,6 +362,7 @@ pub enum LedgeAction {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GroundAttack {
     Utilt,
     Ftilt,
@@ -161,9 +372,20 @@ pub enum GroundAttack {
     Dsmash,
     Fsmash,
     DashAttack,
+    RapidJab,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ThrowDirection {
+    Forward,
+    Back,
+    Up,
+    Down,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AirAttack {
     Nair,
     Uair,
@@ -175,33 +397,33 @@ pub enum AirAttack {
 impl std::fmt::Display for Character {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         static TABLE: &'static [&'static str] = &[
-            "Mario"        ,
-            "Fox"          ,
-            "CaptainFalcon",
-            "DonkeyKong"   ,
-            "Kirby"        ,
-            "Bowser"       ,
-            "Link"         ,
-            "Sheik"        ,
-            "Ness"         ,
-            "Peach"        ,
-            "Popo"         ,
-            "Nana"         ,
-            "Pikachu"      ,
-            "Samus"        ,
-            "Yoshi"        ,
-            "Jigglypuff"   ,
-            "Mewtwo"       ,
-            "Luigi"        ,
-            "Marth"        ,
-            "Zelda"        ,
-            "YoungLink"    ,
-            "DrMario"      ,
-            "Falco"        ,
-            "Pichu"        ,
-            "GameAndWatch" ,
-            "Ganondorf"    ,
-            "Roy"          ,
+            "Mario"           ,
+            "Fox"             ,
+            "Captain Falcon"  ,
+            "Donkey Kong"     ,
+            "Kirby"           ,
+            "Bowser"          ,
+            "Link"            ,
+            "Sheik"           ,
+            "Ness"            ,
+            "Peach"           ,
+            "Popo"            ,
+            "Nana"            ,
+            "Pikachu"         ,
+            "Samus"           ,
+            "Yoshi"           ,
+            "Jigglypuff"      ,
+            "Mewtwo"          ,
+            "Luigi"           ,
+            "Marth"           ,
+            "Zelda"           ,
+            "Young Link"      ,
+            "Dr. Mario"       ,
+            "Falco"           ,
+            "Pichu"           ,
+            "Mr. Game & Watch",
+            "Ganondorf"       ,
+            "Roy"             ,
         ];
 
         write!(f, "{}", TABLE[*self as usize])
@@ -250,6 +472,11 @@ impl StandardActionState {
         }
     }
 
+    /// None for character specific states (ids above 340).
+    pub fn try_from_u16(st: u16) -> Option<Self> {
+        Self::from_u16(st).ok()
+    }
+
     pub fn as_u16(self) -> u16 {
         self as u16
     }
@@ -267,6 +494,31 @@ impl StandardActionState {
         })
     }
 
+    /// Whether the player can freely act out of this state,
+    /// such as standing, moving, falling, crouching, shielding, or hanging on the ledge.
+    pub fn is_actionable(self) -> bool {
+        use StandardActionState::*;
+
+        matches!(self,
+            Wait | WalkSlow | WalkMiddle | WalkFast | Turn | TurnRun | Dash | Run | RunDirect
+            | JumpF | JumpB | JumpAerialF | JumpAerialB
+            | Fall | FallF | FallB | FallAerial | FallAerialF | FallAerialB
+            | Squat | SquatWait | SquatRv | Guard | OttottoWait | CliffWait
+        )
+    }
+
+    pub fn throw_direction(self) -> Option<ThrowDirection> {
+        use StandardActionState::*;
+
+        Some(match self {
+            ThrowF => ThrowDirection::Forward,
+            ThrowB => ThrowDirection::Back,
+            ThrowHi => ThrowDirection::Up,
+            ThrowLw => ThrowDirection::Down,
+            _ => return None,
+        })
+    }
+
     pub fn attack_type(self) -> Option<AttackType> {
         use StandardActionState::*;
         use AirAttack::*;
@@ -276,9 +528,9 @@ impl StandardActionState {
             Attack11 => AttackType::GroundAttack(Jab),
             Attack12 => AttackType::GroundAttack(Jab),
             Attack13 => AttackType::GroundAttack(Jab),
-            Attack100Start => AttackType::GroundAttack(Jab),
-            Attack100Loop => AttackType::GroundAttack(Jab),
-            Attack100End => AttackType::GroundAttack(Jab),
+            Attack100Start => AttackType::GroundAttack(RapidJab),
+            Attack100Loop => AttackType::GroundAttack(RapidJab),
+            Attack100End => AttackType::GroundAttack(RapidJab),
             AttackDash => AttackType::GroundAttack(DashAttack),
             AttackS3Hi => AttackType::GroundAttack(Ftilt),
             AttackS3HiS => AttackType::GroundAttack(Ftilt),
@@ -328,7 +580,7 @@ impl StandardActionState {
             Walk,                //           WalkSlow
             Walk,                //           WalkMiddle
             Walk,                //           WalkFast
-            Ground,              // TODO:      //           Turn
+            Turn,                //           Turn
             DashRun,             //           TurnRun
             DashRun,             //           Dash
             DashRun,             //           Run
@@ -345,9 +597,9 @@ impl StandardActionState {
             Air,                 //           FallAerial
             Air,                 //           FallAerialF
             Air,                 //           FallAerialB
-            GenericInactionable, // TODO:     FallSpecial
-            GenericInactionable, // TODO:     FallSpecialF
-            GenericInactionable, // TODO:     FallSpecialB
+            SpecialFall,         //           FallSpecial
+            SpecialFall,         //           FallSpecialF
+            SpecialFall,         //           FallSpecialB
             Air,                 //           DamageFall
             Crouch,              //           Squat
             Crouch,              //           SquatWait
@@ -493,28 +745,28 @@ impl StandardActionState {
             GenericInactionable, //           GuardOff
             Shield,              // TODO:      //           GuardSetOff
             Shield,              //           GuardReflect
-            GenericInactionable, // TODO:     DownBoundU
-            GenericInactionable, // TODO:     DownWaitU
-            GenericInactionable, // TODO:     DownDamageU
-            GenericInactionable, // TODO:     DownStandU
-            GenericInactionable, // TODO:     DownAttackU
-            GenericInactionable, // TODO:     DownFowardU
-            GenericInactionable, // TODO:     DownBackU
-            GenericInactionable, // TODO:     DownSpotU
-            GenericInactionable, // TODO:     DownBoundD
-            GenericInactionable, // TODO:     DownWaitD
-            GenericInactionable, // TODO:     DownDamageD
-            GenericInactionable, // TODO:     DownStandD
-            GenericInactionable, // TODO:     DownAttackD
-            GenericInactionable, // TODO:     DownFowardD
-            GenericInactionable, // TODO:     DownBackD
-            GenericInactionable, // TODO:     DownSpotD
-            GenericInactionable, // TODO:     Passive
-            GenericInactionable, // TODO:     PassiveStandF
-            GenericInactionable, // TODO:     PassiveStandB
-            GenericInactionable, // TODO:     PassiveWall
-            GenericInactionable, // TODO:     PassiveWallJump
-            GenericInactionable, // TODO:     PassiveCeil
+            Knockdown,           //           DownBoundU
+            Knockdown,           //           DownWaitU
+            Knockdown,           //           DownDamageU
+            Knockdown,           //           DownStandU
+            Knockdown,           //           DownAttackU
+            Knockdown,           //           DownFowardU
+            Knockdown,           //           DownBackU
+            Knockdown,           //           DownSpotU
+            Knockdown,           //           DownBoundD
+            Knockdown,           //           DownWaitD
+            Knockdown,           //           DownDamageD
+            Knockdown,           //           DownStandD
+            Knockdown,           //           DownAttackD
+            Knockdown,           //           DownFowardD
+            Knockdown,           //           DownBackD
+            Knockdown,           //           DownSpotD
+            Knockdown,           //           Passive
+            Knockdown,           //           PassiveStandF
+            Knockdown,           //           PassiveStandB
+            Knockdown,           //           PassiveWall
+            Knockdown,           //           PassiveWallJump
+            Knockdown,           //           PassiveCeil
             GenericInactionable, //           ShieldBreakFly
             GenericInactionable, //           ShieldBreakFall
             GenericInactionable, //           ShieldBreakDownU
@@ -529,10 +781,10 @@ impl StandardActionState {
             Grab,                //           CatchWait
             Grab,                //           CatchAttack
             Grab,                //           CatchCut
-            Grab,                // TODO:        //           ThrowF
-            Grab,                // TODO:        //           ThrowB
-            Grab,                // TODO:        //           ThrowHi
-            Grab,                // TODO:        //           ThrowLw
+            Throw,               //           ThrowF
+            Throw,               //           ThrowB
+            Throw,               //           ThrowHi
+            Throw,               //           ThrowLw
             Hitstun,             //           CapturePulledHi
             Hitstun,             //           CaptureWaitHi
             Hitstun,             //           CaptureDamageHi
@@ -1100,15 +1352,39 @@ impl SpecialActionState {
     }
 
     pub fn as_u16(self) -> u16 {
-        todo!();
-
-        //match self {
-        //    SpecialActionState::Fox(s) => s.as_u16(),
-        //}
+        match self {
+            SpecialActionState::Mario         (s) => s.as_u16(),
+            SpecialActionState::Fox           (s) => s.as_u16(),
+            SpecialActionState::CaptainFalcon (s) => s.as_u16(),
+            SpecialActionState::DonkeyKong    (s) => s.as_u16(),
+            SpecialActionState::Kirby         (s) => s.as_u16(),
+            SpecialActionState::Bowser        (s) => s.as_u16(),
+            SpecialActionState::Link          (s) => s.as_u16(),
+            SpecialActionState::Sheik         (s) => s.as_u16(),
+            SpecialActionState::Ness          (s) => s.as_u16(),
+            SpecialActionState::Peach         (s) => s.as_u16(),
+            SpecialActionState::IceClimbers   (s) => s.as_u16(),
+            SpecialActionState::Pikachu       (s) => s.as_u16(),
+            SpecialActionState::Samus         (s) => s.as_u16(),
+            SpecialActionState::Yoshi         (s) => s.as_u16(),
+            SpecialActionState::Jigglypuff    (s) => s.as_u16(),
+            SpecialActionState::Mewtwo        (s) => s.as_u16(),
+            SpecialActionState::Luigi         (s) => s.as_u16(),
+            SpecialActionState::Marth         (s) => s.as_u16(),
+            SpecialActionState::Zelda         (s) => s.as_u16(),
+            SpecialActionState::YoungLink     (s) => s.as_u16(),
+            SpecialActionState::DrMario       (s) => s.as_u16(),
+            SpecialActionState::Falco         (s) => s.as_u16(),
+            SpecialActionState::Pichu         (s) => s.as_u16(),
+            SpecialActionState::MrGameAndWatch(s) => s.as_u16(),
+            SpecialActionState::Ganondorf     (s) => s.as_u16(),
+            SpecialActionState::Roy           (s) => s.as_u16(),
+        }
     }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SpecialActionState {
     CaptainFalcon  (SpecialActionStateCaptainFalcon ),
     DonkeyKong     (SpecialActionStateDonkeyKong    ),
@@ -1139,6 +1415,7 @@ pub enum SpecialActionState {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SpecialBroadState {
     CaptainFalcon  (SpecialBroadStateCaptainFalcon ),
     DonkeyKong     (SpecialBroadStateDonkeyKong    ),
@@ -1169,6 +1446,7 @@ pub enum SpecialBroadState {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SpecialHighLevelAction {
     CaptainFalcon  (HighLevelActionCaptainFalcon ),
     DonkeyKong     (HighLevelActionDonkeyKong    ),
@@ -1199,6 +1477,7 @@ pub enum SpecialHighLevelAction {
 }
 
 #[derive(Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u16)]
 pub enum StandardActionState {
     DeadDown = 000,
@@ -1545,8 +1824,8 @@ pub enum StandardActionState {
 }
 
 impl HighLevelAction {
-    pub const MAX_VALUE: u8 = 63;
-    pub const VARIANT_COUNT: u8 = 64;
+    pub const MAX_VALUE: u8 = 109;
+    pub const VARIANT_COUNT: u8 = 110;
 
     pub fn from_u8(n: u8) -> Option<Self> {
         use HighLevelAction as HLA;
@@ -1621,11 +1900,117 @@ impl HighLevelAction {
             60 => HLA::RollForward,
             61 => HLA::RollBackward,
             62 => HLA::Crouch,
-            Self::MAX_VALUE => HLA::Hitstun,
+            63 => HLA::Hitstun,
+            64 => HLA::Walljump,
+            65 => HLA::SpecialFall,
+            66 => HLA::TechInPlace,
+            67 => HLA::TechRoll(Direction::Left),
+            68 => HLA::TechRoll(Direction::Right),
+            69 => HLA::TechWall,
+            70 => HLA::TechCeiling,
+            71 => HLA::MissedTech,
+            72 => HLA::MissedTechGetUp,
+            73 => HLA::MissedTechAttack,
+            74 => HLA::MissedTechRoll(Direction::Left),
+            75 => HLA::MissedTechRoll(Direction::Right),
+            76 => return None, // DashDance, count is not stored
+            77 => HLA::Pivot,
+            78 => HLA::ShieldDrop,
+            79 => HLA::GrabWhiff,
+            80 => HLA::Throw(ThrowDirection::Forward),
+            81 => HLA::Throw(ThrowDirection::Back),
+            82 => HLA::Throw(ThrowDirection::Up),
+            83 => HLA::Throw(ThrowDirection::Down),
+            84 => HLA::GroundAttack(GroundAttack::RapidJab),
+            85 => HLA::JabCancel,
+            86 => HLA::Moonwalk(crate::Direction::Left),
+            87 => HLA::Moonwalk(crate::Direction::Right),
+            88 => HLA::GrabRelease,
+            89 => HLA::EmptyPivot,
+            90 => HLA::PivotGrab,
+            91 => return None, // Multishine, count is not stored
+            92 => HLA::Waveshine,
+            93 => HLA::Inactionable,
+            94 => HLA::TechWallJump,
+
+            95 => HLA::DoubleJumpAerial { first_jump: crate::JumpType::Full, attack: AirAttack::Nair },
+            96 => HLA::DoubleJumpAerial { first_jump: crate::JumpType::Full, attack: AirAttack::Uair },
+            97 => HLA::DoubleJumpAerial { first_jump: crate::JumpType::Full, attack: AirAttack::Fair },
+            98 => HLA::DoubleJumpAerial { first_jump: crate::JumpType::Full, attack: AirAttack::Bair },
+            99 => HLA::DoubleJumpAerial { first_jump: crate::JumpType::Full, attack: AirAttack::Dair },
+
+            100 => HLA::DoubleJumpAerial { first_jump: crate::JumpType::Short, attack: AirAttack::Nair },
+            101 => HLA::DoubleJumpAerial { first_jump: crate::JumpType::Short, attack: AirAttack::Uair },
+            102 => HLA::DoubleJumpAerial { first_jump: crate::JumpType::Short, attack: AirAttack::Fair },
+            103 => HLA::DoubleJumpAerial { first_jump: crate::JumpType::Short, attack: AirAttack::Bair },
+            104 => HLA::DoubleJumpAerial { first_jump: crate::JumpType::Short, attack: AirAttack::Dair },
+
+            105 => HLA::ReverseAerialRush(AirAttack::Nair),
+            106 => HLA::ReverseAerialRush(AirAttack::Uair),
+            107 => HLA::ReverseAerialRush(AirAttack::Fair),
+            108 => HLA::ReverseAerialRush(AirAttack::Bair),
+            Self::MAX_VALUE => HLA::ReverseAerialRush(AirAttack::Dair),
             Self::VARIANT_COUNT.. => return None,
         })
     }
 
+    /// Attacks, throws, and specials.
+    pub fn is_attack(self) -> bool {
+        use HighLevelAction as HLA;
+        matches!(self,
+            HLA::GroundAttack(_) | HLA::Aerial(_) | HLA::JumpAerial(_) | HLA::FullhopAerial(_)
+            | HLA::ShorthopAerial(_) | HLA::LedgeAerial(_) | HLA::LedgeAttack 
+            | HLA::Throw(_) | HLA::JabCancel | HLA::Multishine { .. } | HLA::Waveshine
+            | HLA::DoubleJumpAerial { .. } | HLA::ReverseAerialRush(_) | HLA::Special(_)
+        )
+    }
+
+    /// Aerial attacks, including those from a jump or ledge.
+    pub fn is_aerial(self) -> bool {
+        use HighLevelAction as HLA;
+        matches!(self,
+            HLA::Aerial(_) | HLA::JumpAerial(_) | HLA::FullhopAerial(_)
+            | HLA::ShorthopAerial(_) | HLA::LedgeAerial(_) | HLA::DoubleJumpAerial { .. }
+            | HLA::ReverseAerialRush(_)
+        )
+    }
+
+    /// Jumps, dashes, walks, wavedashes and wavelands, including from the ledge.
+    pub fn is_movement(self) -> bool {
+        use HighLevelAction as HLA;
+        matches!(self,
+            HLA::Fullhop | HLA::Shorthop | HLA::AirJump | HLA::Walljump
+            | HLA::LedgeDash | HLA::LedgeJump | HLA::LedgeHop | HLA::LedgeDrop
+            | HLA::WavedashRight | HLA::WavedashDown | HLA::WavedashLeft
+            | HLA::WavelandRight | HLA::WavelandDown | HLA::WavelandLeft
+            | HLA::DashLeft | HLA::DashRight | HLA::WalkLeft | HLA::WalkRight
+            | HLA::DashDance { .. } | HLA::Pivot | HLA::EmptyPivot | HLA::Moonwalk(_)
+        )
+    }
+
+    /// Shield, spotdodge, rolls and airdodges.
+    pub fn is_defensive(self) -> bool {
+        use HighLevelAction as HLA;
+        matches!(self,
+            HLA::Shield | HLA::ShieldDrop | HLA::Spotdodge | HLA::RollForward | HLA::RollBackward
+            | HLA::LedgeRoll | HLA::Airdodge
+        )
+    }
+
+    pub fn is_wait(self) -> bool {
+        use HighLevelAction as HLA;
+        matches!(self, HLA::GroundWait | HLA::AirWait | HLA::LedgeWait | HLA::Crouch)
+    }
+
+    pub fn is_wavedash(self) -> bool {
+        use HighLevelAction as HLA;
+        matches!(self, HLA::WavedashRight | HLA::WavedashDown | HLA::WavedashLeft)
+    }
+
+    /// Inverse of `from_u8` for standard actions. Special actions follow at `VARIANT_COUNT + s.as_u16()`.
+    ///
+    /// Encoding change: codes up to 64 (`Walljump`) are unchanged, but specials moved from `64 + s` to `110 + s`
+    /// to make room for the actions added since. Codes stored before this must be re-encoded.
     pub fn as_u16(self) -> u16 {
         use HighLevelAction as HLA;
         match self {
@@ -1701,20 +2086,126 @@ impl HighLevelAction {
             HLA::Crouch => 62,
             HLA::Hitstun => 63,
             HLA::Walljump => 64,
-            HLA::Special(s) => 64 + s.as_u16(), // TODO not backwards compatible
+            HLA::SpecialFall => 65,
+            HLA::TechInPlace => 66,
+            HLA::TechRoll(Direction::Left) => 67,
+            HLA::TechRoll(Direction::Right) => 68,
+            HLA::TechWall => 69,
+            HLA::TechCeiling => 70,
+            HLA::MissedTech => 71,
+            HLA::MissedTechGetUp => 72,
+            HLA::MissedTechAttack => 73,
+            HLA::MissedTechRoll(Direction::Left) => 74,
+            HLA::MissedTechRoll(Direction::Right) => 75,
+            HLA::DashDance { .. } => 76, // count is not stored
+            HLA::Pivot => 77,
+            HLA::ShieldDrop => 78,
+            HLA::GrabWhiff => 79,
+            HLA::Throw(ThrowDirection::Forward) => 80,
+            HLA::Throw(ThrowDirection::Back) => 81,
+            HLA::Throw(ThrowDirection::Up) => 82,
+            HLA::Throw(ThrowDirection::Down) => 83,
+            HLA::GroundAttack(GroundAttack::RapidJab) => 84,
+            HLA::JabCancel => 85,
+            HLA::Moonwalk(crate::Direction::Left) => 86,
+            HLA::Moonwalk(crate::Direction::Right) => 87,
+            HLA::GrabRelease => 88,
+            HLA::EmptyPivot => 89,
+            HLA::PivotGrab => 90,
+            HLA::Multishine { .. } => 91, // count is not stored
+            HLA::Waveshine => 92,
+            HLA::Inactionable => 93,
+            HLA::TechWallJump => 94,
+            HLA::DoubleJumpAerial { first_jump: crate::JumpType::Full, attack: AirAttack::Nair } => 95,
+            HLA::DoubleJumpAerial { first_jump: crate::JumpType::Full, attack: AirAttack::Uair } => 96,
+            HLA::DoubleJumpAerial { first_jump: crate::JumpType::Full, attack: AirAttack::Fair } => 97,
+            HLA::DoubleJumpAerial { first_jump: crate::JumpType::Full, attack: AirAttack::Bair } => 98,
+            HLA::DoubleJumpAerial { first_jump: crate::JumpType::Full, attack: AirAttack::Dair } => 99,
+            HLA::DoubleJumpAerial { first_jump: crate::JumpType::Short, attack: AirAttack::Nair } => 100,
+            HLA::DoubleJumpAerial { first_jump: crate::JumpType::Short, attack: AirAttack::Uair } => 101,
+            HLA::DoubleJumpAerial { first_jump: crate::JumpType::Short, attack: AirAttack::Fair } => 102,
+            HLA::DoubleJumpAerial { first_jump: crate::JumpType::Short, attack: AirAttack::Bair } => 103,
+            HLA::DoubleJumpAerial { first_jump: crate::JumpType::Short, attack: AirAttack::Dair } => 104,
+            HLA::ReverseAerialRush(AirAttack::Nair) => 105,
+            HLA::ReverseAerialRush(AirAttack::Uair) => 106,
+            HLA::ReverseAerialRush(AirAttack::Fair) => 107,
+            HLA::ReverseAerialRush(AirAttack::Bair) => 108,
+            HLA::ReverseAerialRush(AirAttack::Dair) => 109,
+            HLA::Special(s) => Self::VARIANT_COUNT as u16 + s.as_u16(),
         }
     }
 }
 
 impl SpecialHighLevelAction {
+    /// Unique across characters, ordered by character then variant.
     pub fn as_u16(self) -> u16 {
-        todo!();
-        // use shla::VARIANT_NAME to get this
-        
-        //match self {
-        //    SpecialHighLevelAction::Fox(f) => f as u16,
-        //    SpecialHighLevelAction::Falco(f) => f as u16,
-        //}
+        const COUNTS: [usize; 26] = [
+            HighLevelActionCaptainFalcon::VARIANT_COUNT,
+            HighLevelActionDonkeyKong::VARIANT_COUNT,
+            HighLevelActionFox::VARIANT_COUNT,
+            HighLevelActionMrGameAndWatch::VARIANT_COUNT,
+            HighLevelActionKirby::VARIANT_COUNT,
+            HighLevelActionBowser::VARIANT_COUNT,
+            HighLevelActionLink::VARIANT_COUNT,
+            HighLevelActionLuigi::VARIANT_COUNT,
+            HighLevelActionMario::VARIANT_COUNT,
+            HighLevelActionMarth::VARIANT_COUNT,
+            HighLevelActionMewtwo::VARIANT_COUNT,
+            HighLevelActionNess::VARIANT_COUNT,
+            HighLevelActionPeach::VARIANT_COUNT,
+            HighLevelActionPikachu::VARIANT_COUNT,
+            HighLevelActionIceClimbers::VARIANT_COUNT,
+            HighLevelActionJigglypuff::VARIANT_COUNT,
+            HighLevelActionSamus::VARIANT_COUNT,
+            HighLevelActionYoshi::VARIANT_COUNT,
+            HighLevelActionZelda::VARIANT_COUNT,
+            HighLevelActionSheik::VARIANT_COUNT,
+            HighLevelActionFalco::VARIANT_COUNT,
+            HighLevelActionYoungLink::VARIANT_COUNT,
+            HighLevelActionDrMario::VARIANT_COUNT,
+            HighLevelActionRoy::VARIANT_COUNT,
+            HighLevelActionPichu::VARIANT_COUNT,
+            HighLevelActionGanondorf::VARIANT_COUNT,
+        ];
+
+        const OFFSETS: [u16; 26] = {
+            let mut offsets = [0u16; 26];
+            let mut i = 1;
+            while i < 26 {
+                offsets[i] = offsets[i-1] + COUNTS[i-1] as u16;
+                i += 1;
+            }
+            offsets
+        };
+
+        match self {
+            SpecialHighLevelAction::CaptainFalcon(a) => OFFSETS[0] + a as u16,
+            SpecialHighLevelAction::DonkeyKong(a) => OFFSETS[1] + a as u16,
+            SpecialHighLevelAction::Fox(a) => OFFSETS[2] + a as u16,
+            SpecialHighLevelAction::MrGameAndWatch(a) => OFFSETS[3] + a as u16,
+            SpecialHighLevelAction::Kirby(a) => OFFSETS[4] + a as u16,
+            SpecialHighLevelAction::Bowser(a) => OFFSETS[5] + a as u16,
+            SpecialHighLevelAction::Link(a) => OFFSETS[6] + a as u16,
+            SpecialHighLevelAction::Luigi(a) => OFFSETS[7] + a as u16,
+            SpecialHighLevelAction::Mario(a) => OFFSETS[8] + a as u16,
+            SpecialHighLevelAction::Marth(a) => OFFSETS[9] + a as u16,
+            SpecialHighLevelAction::Mewtwo(a) => OFFSETS[10] + a as u16,
+            SpecialHighLevelAction::Ness(a) => OFFSETS[11] + a as u16,
+            SpecialHighLevelAction::Peach(a) => OFFSETS[12] + a as u16,
+            SpecialHighLevelAction::Pikachu(a) => OFFSETS[13] + a as u16,
+            SpecialHighLevelAction::IceClimbers(a) => OFFSETS[14] + a as u16,
+            SpecialHighLevelAction::Jigglypuff(a) => OFFSETS[15] + a as u16,
+            SpecialHighLevelAction::Samus(a) => OFFSETS[16] + a as u16,
+            SpecialHighLevelAction::Yoshi(a) => OFFSETS[17] + a as u16,
+            SpecialHighLevelAction::Zelda(a) => OFFSETS[18] + a as u16,
+            SpecialHighLevelAction::Sheik(a) => OFFSETS[19] + a as u16,
+            SpecialHighLevelAction::Falco(a) => OFFSETS[20] + a as u16,
+            SpecialHighLevelAction::YoungLink(a) => OFFSETS[21] + a as u16,
+            SpecialHighLevelAction::DrMario(a) => OFFSETS[22] + a as u16,
+            SpecialHighLevelAction::Roy(a) => OFFSETS[23] + a as u16,
+            SpecialHighLevelAction::Pichu(a) => OFFSETS[24] + a as u16,
+            SpecialHighLevelAction::Ganondorf(a) => OFFSETS[25] + a as u16,
+        }
     }
 }
 
@@ -1736,6 +2227,7 @@ impl fmt::Display for StandardBroadState {
             SBS::Air                 => write!(f, "Air"),
             SBS::Airdodge            => write!(f, "Airdodge"),
             SBS::SpecialLanding      => write!(f, "Special land"),
+            SBS::SpecialFall         => write!(f, "Special fall"),
             SBS::Ground              => write!(f, "Standing"),
             SBS::Walk                => write!(f, "Walk"),
             SBS::DashRun             => write!(f, "Dash"),
@@ -1750,6 +2242,9 @@ impl fmt::Display for StandardBroadState {
             SBS::Grab                => write!(f, "Grab"),
             SBS::Roll                => write!(f, "Roll"),
             SBS::Spotdodge           => write!(f, "Spotdodge"),
+            SBS::Knockdown           => write!(f, "Knockdown"),
+            SBS::Turn                => write!(f, "Turn"),
+            SBS::Throw               => write!(f, "Throw"),
         }
     }
 }
@@ -1830,6 +2325,35 @@ impl fmt::Display for HighLevelAction {
             Crouch => write!(f, "Crouch"),
             Hitstun => write!(f, "In hit"),
             Walljump => write!(f, "Walljump"),
+            SpecialFall => write!(f, "Special fall"),
+            TechInPlace => write!(f, "Tech in place"),
+            TechRoll(crate::Direction::Left) => write!(f, "Tech roll left"),
+            TechRoll(crate::Direction::Right) => write!(f, "Tech roll right"),
+            TechWall => write!(f, "Wall tech"),
+            TechCeiling => write!(f, "Ceiling tech"),
+            MissedTech => write!(f, "Missed tech"),
+            MissedTechGetUp => write!(f, "Missed tech getup"),
+            MissedTechAttack => write!(f, "Getup attack"),
+            MissedTechRoll(crate::Direction::Left) => write!(f, "Getup roll left"),
+            MissedTechRoll(crate::Direction::Right) => write!(f, "Getup roll right"),
+            DashDance { count } => write!(f, "Dash dance ({})", count),
+            Pivot => write!(f, "Pivot"),
+            ShieldDrop => write!(f, "Shield drop"),
+            GrabWhiff => write!(f, "Missed grab"),
+            Throw(dir) => write!(f, "{}", dir),
+            JabCancel => write!(f, "Jab cancel"),
+            Moonwalk(crate::Direction::Left) => write!(f, "Moonwalk left"),
+            Moonwalk(crate::Direction::Right) => write!(f, "Moonwalk right"),
+            GrabRelease => write!(f, "Grab release"),
+            EmptyPivot => write!(f, "Empty pivot"),
+            PivotGrab => write!(f, "Pivot grab"),
+            Multishine { count } => write!(f, "Multishine ({})", count),
+            Waveshine => write!(f, "Waveshine"),
+            Inactionable => write!(f, "Inactionable"),
+            TechWallJump => write!(f, "Wall tech jump"),
+            DoubleJumpAerial { first_jump: crate::JumpType::Full, attack } => write!(f, "Fullhop double jump {}", attack),
+            DoubleJumpAerial { first_jump: crate::JumpType::Short, attack } => write!(f, "Shorthop double jump {}", attack),
+            ReverseAerialRush(at) => write!(f, "RAR {}", at),
             Special(s) => write!(f, "{}", s),
         }
     }
@@ -1869,6 +2393,17 @@ impl fmt::Display for SpecialHighLevelAction {
     }
 }
 
+impl fmt::Display for ThrowDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThrowDirection::Forward => write!(f, "Forward throw"),
+            ThrowDirection::Back => write!(f, "Back throw"),
+            ThrowDirection::Up => write!(f, "Up throw"),
+            ThrowDirection::Down => write!(f, "Down throw"),
+        }
+    }
+}
+
 impl fmt::Display for AirAttack {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use AirAttack::*;
@@ -1894,6 +2429,95 @@ impl fmt::Display for GroundAttack {
             Dsmash => write!(f, "Dsmash"),
             Fsmash => write!(f, "Fsmash"),
             DashAttack => write!(f, "Dash attack"),
+            RapidJab => write!(f, "Rapid jab"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard_broad_state_round_trip() {
+        for n in 0..StandardBroadState::VARIANT_COUNT {
+            assert_eq!(StandardBroadState::from_u8(n).map(StandardBroadState::as_u8), Some(n));
+        }
+        assert_eq!(StandardBroadState::from_u8(StandardBroadState::VARIANT_COUNT), None);
+    }
+
+    #[test]
+    fn special_broad_state_round_trip() {
+        macro_rules! check {
+            ($($sbs:ident),*) => {$(
+                for n in 0..$sbs::VARIANT_COUNT as u8 {
+                    assert_eq!($sbs::from_u8(n).map($sbs::as_u8), Some(n), stringify!($sbs));
+                }
+                assert_eq!($sbs::from_u8($sbs::VARIANT_COUNT as u8), None, stringify!($sbs));
+            )*}
         }
+
+        check!(
+            SpecialBroadStateCaptainFalcon, SpecialBroadStateDonkeyKong, SpecialBroadStateFox,
+            SpecialBroadStateMrGameAndWatch, SpecialBroadStateKirby, SpecialBroadStateBowser,
+            SpecialBroadStateLink, SpecialBroadStateLuigi, SpecialBroadStateMario,
+            SpecialBroadStateMarth, SpecialBroadStateMewtwo, SpecialBroadStateNess,
+            SpecialBroadStatePeach, SpecialBroadStatePikachu, SpecialBroadStateIceClimbers,
+            SpecialBroadStateJigglypuff, SpecialBroadStateSamus, SpecialBroadStateYoshi,
+            SpecialBroadStateZelda, SpecialBroadStateSheik, SpecialBroadStateFalco,
+            SpecialBroadStateYoungLink, SpecialBroadStateDrMario, SpecialBroadStateRoy,
+            SpecialBroadStatePichu, SpecialBroadStateGanondorf
+        );
+    }
+
+    #[test]
+    fn broad_state_round_trip() {
+        let special_count: usize = SpecialBroadState::COUNTS.iter().sum();
+        let count = StandardBroadState::VARIANT_COUNT as u16 + special_count as u16;
+        for n in 0..count {
+            assert_eq!(BroadState::from_u16(n).map(BroadState::as_u16), Some(n));
+        }
+        assert_eq!(BroadState::from_u16(count), None);
+    }
+
+    #[test]
+    fn high_level_action_round_trip() {
+        for n in 0..HighLevelAction::VARIANT_COUNT {
+            match HighLevelAction::from_u8(n) {
+                Some(hla) => assert_eq!(hla.as_u16(), n as u16, "{:?}", hla),
+                // counts are not stored
+                None => assert!(n == 76 || n == 91, "{}", n),
+            }
+        }
+        assert_eq!(HighLevelAction::from_u8(HighLevelAction::VARIANT_COUNT), None);
+        assert_eq!(HighLevelAction::DashDance { count: 3 }.as_u16(), 76);
+        assert_eq!(HighLevelAction::Multishine { count: 3 }.as_u16(), 91);
+    }
+
+    #[test]
+    fn special_high_level_action_codes_are_unique() {
+        let mut codes = std::collections::HashSet::new();
+        macro_rules! insert {
+            ($($char:ident: $shla:ident),*) => {$(
+                for n in 0..$shla::VARIANT_COUNT as u8 {
+                    let hla = HighLevelAction::Special(SpecialHighLevelAction::$char($shla::from_u8(n).unwrap()));
+                    assert!(hla.as_u16() >= HighLevelAction::VARIANT_COUNT as u16);
+                    assert!(codes.insert(hla.as_u16()), "{:?}", hla);
+                }
+            )*}
+        }
+
+        insert!(
+            CaptainFalcon: HighLevelActionCaptainFalcon, DonkeyKong: HighLevelActionDonkeyKong,
+            Fox: HighLevelActionFox, MrGameAndWatch: HighLevelActionMrGameAndWatch,
+            Kirby: HighLevelActionKirby, Bowser: HighLevelActionBowser, Link: HighLevelActionLink,
+            Luigi: HighLevelActionLuigi, Mario: HighLevelActionMario, Marth: HighLevelActionMarth,
+            Mewtwo: HighLevelActionMewtwo, Ness: HighLevelActionNess, Peach: HighLevelActionPeach,
+            Pikachu: HighLevelActionPikachu, IceClimbers: HighLevelActionIceClimbers,
+            Jigglypuff: HighLevelActionJigglypuff, Samus: HighLevelActionSamus, Yoshi: HighLevelActionYoshi,
+            Zelda: HighLevelActionZelda, Sheik: HighLevelActionSheik, Falco: HighLevelActionFalco,
+            YoungLink: HighLevelActionYoungLink, DrMario: HighLevelActionDrMario, Roy: HighLevelActionRoy,
+            Pichu: HighLevelActionPichu, Ganondorf: HighLevelActionGanondorf
+        );
     }
 }
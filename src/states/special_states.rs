@@ -18,7 +18,13 @@ pub fn implemented_character(c: Character) -> bool {
         | Character::Sheik
         | Character::Samus
         | Character::Jigglypuff
-        | Character::CaptainFalcon => true,
+        | Character::CaptainFalcon
+        | Character::Pikachu
+        | Character::Pichu
+        | Character::Ganondorf
+        | Character::Luigi
+        | Character::Mario
+        | Character::DrMario => true,
         _ => false,
     }
 }
@@ -55,16 +61,19 @@ macro_rules! special_states {
         { $($nm:ident = $n:expr => $bs:ident, $st:expr),*$(,)? }
     ) => {
         #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub enum $sas {
             $($nm = $n),*
         }
 
         #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub enum $sbs {
             $($bsnm),*
         }
 
         #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub enum $shla {
             $($bsnm,)*
             $( $($jparam,)* )*
@@ -98,6 +107,18 @@ macro_rules! special_states {
         }
 
         impl $sbs {
+            #[allow(unused, non_snake_case)]
+            pub const VARIANT_COUNT: usize = $( {let $bsnm: u8; 1} + )* 0;
+
+            pub fn from_u8(n: u8) -> Option<Self> {
+                const VARIANTS: &[$sbs] = &[$($sbs::$bsnm),*];
+                VARIANTS.get(n as usize).copied()
+            }
+
+            pub fn as_u8(self) -> u8 {
+                self as u8
+            }
+
             pub fn parse_special(self, consumer: &mut ActionBuilder) -> Result<Action, ParseError> {
                 use $sbs::*;
 
@@ -131,6 +152,11 @@ macro_rules! special_states {
             #[allow(unused, non_snake_case)]
             pub const VARIANT_COUNT: usize = $( {let $bsnm: u8; 1} + )* $( $( {let $jparam: u8; 1} + )* )* 0;
 
+            pub fn from_u8(n: u8) -> Option<Self> {
+                const VARIANTS: &[$shla] = &[$($shla::$bsnm,)* $( $($shla::$jparam,)* )*];
+                VARIANTS.get(n as usize).copied()
+            }
+
             pub fn as_string(&self) -> &'static str {
                 use $shla::*;
                 match self {
@@ -816,44 +842,55 @@ special_states! {
 special_states! {
     Luigi, SpecialActionStateLuigi, 
     SpecialBroadStateLuigi, HighLevelActionLuigi
-    { Todo, ParseAll, NoJumpVariants(), },
     {
-        FireballGround                   = 341 => Todo, "Todo",
-        FireballAir                      = 342 => Todo, "Todo",
-        GreenMissileGroundStartup        = 343 => Todo, "Todo",
-        GreenMissileGroundCharge         = 344 => Todo, "Todo",
-        Unknown345                       = 345 => Todo, "Todo",
-        GreenMissileGroundLanding        = 346 => Todo, "Todo",
-        GreenMissileGroundTakeoff        = 347 => Todo, "Todo",
-        GreenMissileGroundTakeoffMisfire = 348 => Todo, "Todo",
-        GreenMissileAirStartup           = 349 => Todo, "Todo",
-        GreenMissileAirCharge            = 350 => Todo, "Todo",
-        GreenMissileAir                  = 351 => Todo, "Todo",
-        GreenMissileAirEnd               = 352 => Todo, "Todo",
-        GreenMissileAirTakeoff           = 353 => Todo, "Todo",
-        GreenMissileAirTakeoffMisfire    = 354 => Todo, "Todo",
-        SuperJumpPunchGround             = 355 => Todo, "Todo",
-        SuperJumpPunchAir                = 356 => Todo, "Todo",
-        CycloneGround                    = 357 => Todo, "Todo",
-        CycloneAir                       = 358 => Todo, "Todo",
+        Fireball, ParseAll, NoJumpVariants(),
+        GreenMissile, ParseAll, NoJumpVariants(),
+        SuperJumpPunch, ParseAll, NoJumpVariants(),
+        Cyclone, ParseAll, NoJumpVariants(),
+    },
+    {
+        FireballGround                   = 341 => Fireball      , "SpecialN",
+        FireballAir                      = 342 => Fireball      , "SpecialAirN",
+        GreenMissileGroundStartup        = 343 => GreenMissile  , "SpecialSStart",
+        GreenMissileGroundCharge         = 344 => GreenMissile  , "SpecialSHold",
+        Unknown345                       = 345 => GreenMissile  , "SpecialSHold",
+        GreenMissileGroundLanding        = 346 => GreenMissile  , "SpecialSEnd",
+        GreenMissileGroundTakeoff        = 347 => GreenMissile  , "SpecialS",
+        GreenMissileGroundTakeoffMisfire = 348 => GreenMissile  , "SpecialS",
+        GreenMissileAirStartup           = 349 => GreenMissile  , "SpecialAirSStart",
+        GreenMissileAirCharge            = 350 => GreenMissile  , "SpecialAirSHold",
+        GreenMissileAir                  = 351 => GreenMissile  , "SpecialAirS",
+        GreenMissileAirEnd               = 352 => GreenMissile  , "SpecialAirSEnd",
+        GreenMissileAirTakeoff           = 353 => GreenMissile  , "SpecialAirS",
+        GreenMissileAirTakeoffMisfire    = 354 => GreenMissile  , "SpecialAirS",
+        SuperJumpPunchGround             = 355 => SuperJumpPunch, "SpecialHi",
+        SuperJumpPunchAir                = 356 => SuperJumpPunch, "SpecialAirHi",
+        CycloneGround                    = 357 => Cyclone       , "SpecialLw",
+        CycloneAir                       = 358 => Cyclone       , "SpecialAirLw",
     }
 }
 
 special_states! {
     Mario, SpecialActionStateMario, 
     SpecialBroadStateMario, HighLevelActionMario
-    { Todo, ParseAll, NoJumpVariants(), },
     {
-        Unknown341           = 341 => Todo, "Todo",
-        Unknown342           = 342 => Todo, "Todo",
-        FireballGround       = 343 => Todo, "Todo",
-        FireballAir          = 344 => Todo, "Todo",
-        CapeGround           = 345 => Todo, "Todo",
-        CapeAir              = 346 => Todo, "Todo",
-        SuperJumpPunchGround = 347 => Todo, "Todo",
-        SuperJumpPunchAir    = 348 => Todo, "Todo",
-        TornadoGround        = 349 => Todo, "Todo",
-        TornadoAir           = 350 => Todo, "Todo",
+        Unknown, ParseAll, NoJumpVariants(),
+        Fireball, ParseAll, NoJumpVariants(),
+        Cape, ParseAll, NoJumpVariants(),
+        SuperJumpPunch, ParseAll, NoJumpVariants(),
+        Tornado, ParseAll, NoJumpVariants(),
+    },
+    {
+        Unknown341           = 341 => Unknown       , "Unknown",
+        Unknown342           = 342 => Unknown       , "Unknown",
+        FireballGround       = 343 => Fireball      , "SpecialN",
+        FireballAir          = 344 => Fireball      , "SpecialAirN",
+        CapeGround           = 345 => Cape          , "SpecialS",
+        CapeAir              = 346 => Cape          , "SpecialAirS",
+        SuperJumpPunchGround = 347 => SuperJumpPunch, "SpecialHi",
+        SuperJumpPunchAir    = 348 => SuperJumpPunch, "SpecialAirHi",
+        TornadoGround        = 349 => Tornado       , "SpecialLw",
+        TornadoAir           = 350 => Tornado       , "SpecialAirLw",
     }
 }
 
@@ -932,34 +969,39 @@ special_states! {
 special_states! {
     Pikachu, SpecialActionStatePikachu, 
     SpecialBroadStatePikachu, HighLevelActionPikachu
-    { Todo, ParseAll, NoJumpVariants(), },
     {
-        ThunderJoltGround        = 341 => Todo, "Todo",
-        ThunderJoltAir           = 342 => Todo, "Todo",
-        SkullBashGroundStartup   = 343 => Todo, "Todo",
-        SkullBashGroundCharge    = 344 => Todo, "Todo",
-        Unknown345               = 345 => Todo, "Todo",
-        SkullBashGroundLanding   = 346 => Todo, "Todo",
-        SkullBashGroundTakeoff   = 347 => Todo, "Todo",
-        SkullBashAirStartup      = 348 => Todo, "Todo",
-        SkullBashAirCharge       = 349 => Todo, "Todo",
-        SkullBashAir             = 350 => Todo, "Todo",
-        SkullBashAirEnd          = 351 => Todo, "Todo",
-        SkullBashAirTakeoff      = 352 => Todo, "Todo",
-        QuickAttackGroundStartup = 353 => Todo, "Todo",
-        QuickAttackGround        = 354 => Todo, "Todo",
-        QuickAttackGroundEnd     = 355 => Todo, "Todo",
-        QuickAttackAirStartup    = 356 => Todo, "Todo",
-        QuickAttackAir           = 357 => Todo, "Todo",
-        QuickAttackAirEnd        = 358 => Todo, "Todo",
-        ThunderGroundStartup     = 359 => Todo, "Todo",
-        ThunderGround            = 360 => Todo, "Todo",
-        ThunderGroundHit         = 361 => Todo, "Todo",
-        ThunderGroundEnd         = 362 => Todo, "Todo",
-        ThunderAirStartup        = 363 => Todo, "Todo",
-        ThunderAir               = 364 => Todo, "Todo",
-        ThunderAirHit            = 365 => Todo, "Todo",
-        ThunderAirEnd            = 366 => Todo, "Todo",
+        ThunderJolt, ParseAll, NoJumpVariants(),
+        SkullBash, ParseAll, NoJumpVariants(),
+        QuickAttack, ParseAll, NoJumpVariants(),
+        Thunder, ParseAll, NoJumpVariants(),
+    },
+    {
+        ThunderJoltGround        = 341 => ThunderJolt, "SpecialN",
+        ThunderJoltAir           = 342 => ThunderJolt, "SpecialAirN",
+        SkullBashGroundStartup   = 343 => SkullBash  , "SpecialSStart",
+        SkullBashGroundCharge    = 344 => SkullBash  , "SpecialSHold",
+        Unknown345               = 345 => SkullBash  , "SpecialSHold",
+        SkullBashGroundLanding   = 346 => SkullBash  , "SpecialSEnd",
+        SkullBashGroundTakeoff   = 347 => SkullBash  , "SpecialS",
+        SkullBashAirStartup      = 348 => SkullBash  , "SpecialAirSStart",
+        SkullBashAirCharge       = 349 => SkullBash  , "SpecialAirSHold",
+        SkullBashAir             = 350 => SkullBash  , "SpecialAirS",
+        SkullBashAirEnd          = 351 => SkullBash  , "SpecialAirSEnd",
+        SkullBashAirTakeoff      = 352 => SkullBash  , "SpecialAirS",
+        QuickAttackGroundStartup = 353 => QuickAttack, "SpecialHiStart",
+        QuickAttackGround        = 354 => QuickAttack, "SpecialHi",
+        QuickAttackGroundEnd     = 355 => QuickAttack, "SpecialHiEnd",
+        QuickAttackAirStartup    = 356 => QuickAttack, "SpecialAirHiStart",
+        QuickAttackAir           = 357 => QuickAttack, "SpecialAirHi",
+        QuickAttackAirEnd        = 358 => QuickAttack, "SpecialAirHiEnd",
+        ThunderGroundStartup     = 359 => Thunder    , "SpecialLwStart",
+        ThunderGround            = 360 => Thunder    , "SpecialLwLoop",
+        ThunderGroundHit         = 361 => Thunder    , "SpecialLwLoop2",
+        ThunderGroundEnd         = 362 => Thunder    , "SpecialLwEnd",
+        ThunderAirStartup        = 363 => Thunder    , "SpecialAirLwStart",
+        ThunderAir               = 364 => Thunder    , "SpecialAirLwLoop",
+        ThunderAirHit            = 365 => Thunder    , "SpecialAirLwLoop2",
+        ThunderAirEnd            = 366 => Thunder    , "SpecialAirLwEnd",
     }
 }
 
@@ -999,40 +1041,46 @@ special_states! {
 special_states! {
     Jigglypuff, SpecialActionStateJigglypuff, 
     SpecialBroadStateJigglypuff, HighLevelActionJigglypuff
-    { Todo, ParseAll, NoJumpVariants(), },
     {
-        Jump2                                = 341 => Todo, "Todo",
-        Jump3                                = 342 => Todo, "Todo",
-        Jump4                                = 343 => Todo, "Todo",
-        Jump5                                = 344 => Todo, "Todo",
-        Jump6                                = 345 => Todo, "Todo",
-        RolloutGroundStartChargeRight        = 346 => Todo, "Todo",
-        RolloutGroundStartChargeLeft         = 347 => Todo, "Todo",
-        RolloutGroundChargeLoop              = 348 => Todo, "Todo",
-        RolloutGroundFullyCharged            = 349 => Todo, "Todo",
-        RolloutGroundChargeRelease           = 350 => Todo, "Todo",
-        RolloutGroundStartTurn               = 351 => Todo, "Todo",
-        RolloutGroundEndRight                = 352 => Todo, "Todo",
-        RolloutGroundEndLeft                 = 353 => Todo, "Todo",
-        RolloutAirStartChargeRight           = 354 => Todo, "Todo",
-        RolloutAirStartChargeLeft            = 355 => Todo, "Todo",
-        RolloutAirChargeLoop                 = 356 => Todo, "Todo",
-        RolloutAirFullyCharged               = 357 => Todo, "Todo",
-        RolloutAirChargeRelease              = 358 => Todo, "Todo",
-        Unknown359                           = 359 => Todo, "Todo",
-        RolloutAirEndRight                   = 360 => Todo, "Todo",
-        RolloutAirEndLeft                    = 361 => Todo, "Todo",
-        RolloutHit                           = 362 => Todo, "Todo",
-        PoundGround                          = 363 => Todo, "Todo",
-        PoundAir                             = 364 => Todo, "Todo",
-        SingGroundLeft                       = 365 => Todo, "Todo",
-        SingAirLeft                          = 366 => Todo, "Todo",
-        SingGroundRight                      = 367 => Todo, "Todo",
-        SingAirRight                         = 368 => Todo, "Todo",
-        RestGroundLeft                       = 369 => Todo, "Todo",
-        RestAirLeft                          = 370 => Todo, "Todo",
-        RestGroundRight                      = 371 => Todo, "Todo",
-        RestAirRight                         = 372 => Todo, "Todo",
+        Jump, ParseAll, NoJumpVariants(),
+        Rollout, ParseAll, NoJumpVariants(),
+        Pound, ParseAll, NoJumpVariants(),
+        Sing, ParseAll, NoJumpVariants(),
+        Rest, ParseAll, NoJumpVariants(),
+    },
+    {
+        Jump2                         = 341 => Jump   , "JumpAerialF1",
+        Jump3                         = 342 => Jump   , "JumpAerialF2",
+        Jump4                         = 343 => Jump   , "JumpAerialF3",
+        Jump5                         = 344 => Jump   , "JumpAerialF4",
+        Jump6                         = 345 => Jump   , "JumpAerialF5",
+        RolloutGroundStartChargeRight = 346 => Rollout, "SpecialNStartR",
+        RolloutGroundStartChargeLeft  = 347 => Rollout, "SpecialNStartL",
+        RolloutGroundChargeLoop       = 348 => Rollout, "SpecialNLoop",
+        RolloutGroundFullyCharged     = 349 => Rollout, "SpecialNFull",
+        RolloutGroundChargeRelease    = 350 => Rollout, "SpecialNRoll",
+        RolloutGroundStartTurn        = 351 => Rollout, "SpecialNTurn",
+        RolloutGroundEndRight         = 352 => Rollout, "SpecialNEndR",
+        RolloutGroundEndLeft          = 353 => Rollout, "SpecialNEndL",
+        RolloutAirStartChargeRight    = 354 => Rollout, "SpecialAirNStartR",
+        RolloutAirStartChargeLeft     = 355 => Rollout, "SpecialAirNStartL",
+        RolloutAirChargeLoop          = 356 => Rollout, "SpecialAirNLoop",
+        RolloutAirFullyCharged        = 357 => Rollout, "SpecialAirNFull",
+        RolloutAirChargeRelease       = 358 => Rollout, "SpecialAirNRoll",
+        Unknown359                    = 359 => Rollout, "SpecialAirNTurn",
+        RolloutAirEndRight            = 360 => Rollout, "SpecialAirNEndR",
+        RolloutAirEndLeft             = 361 => Rollout, "SpecialAirNEndL",
+        RolloutHit                    = 362 => Rollout, "SpecialNHit",
+        PoundGround                   = 363 => Pound  , "SpecialS",
+        PoundAir                      = 364 => Pound  , "SpecialAirS",
+        SingGroundLeft                = 365 => Sing   , "SpecialHiL",
+        SingAirLeft                   = 366 => Sing   , "SpecialAirHiL",
+        SingGroundRight               = 367 => Sing   , "SpecialHiR",
+        SingAirRight                  = 368 => Sing   , "SpecialAirHiR",
+        RestGroundLeft                = 369 => Rest   , "SpecialLwL",
+        RestAirLeft                   = 370 => Rest   , "SpecialAirLwL",
+        RestGroundRight               = 371 => Rest   , "SpecialLwR",
+        RestAirRight                  = 372 => Rest   , "SpecialAirLwR",
     }
 }
 
@@ -1130,18 +1178,25 @@ special_states! {
 special_states! {
     DrMario, SpecialActionStateDrMario, 
     SpecialBroadStateDrMario, HighLevelActionDrMario
-    { Todo, ParseAll, NoJumpVariants(), },
     {
-        TauntR                = 341 => Todo, "Todo",
-        Unknown342            = 342 => Todo, "Todo",
-        MegavitaminGround     = 343 => Todo, "Todo",
-        MegavitaminAir        = 344 => Todo, "Todo",
-        SuperSheetGround      = 345 => Todo, "Todo",
-        SuperSheetAir         = 346 => Todo, "Todo",
-        SuperJumpPunchGround  = 347 => Todo, "Todo",
-        SuperJumpPunchAir     = 348 => Todo, "Todo",
-        TornadoGround         = 349 => Todo, "Todo",
-        TornadoAir            = 350 => Todo, "Todo",
+        Taunt, ParseAll, NoJumpVariants(),
+        Unknown, ParseAll, NoJumpVariants(),
+        Megavitamin, ParseAll, NoJumpVariants(),
+        SuperSheet, ParseAll, NoJumpVariants(),
+        SuperJumpPunch, ParseAll, NoJumpVariants(),
+        Tornado, ParseAll, NoJumpVariants(),
+    },
+    {
+        TauntR               = 341 => Taunt         , "AppealR",
+        Unknown342           = 342 => Unknown       , "Unknown",
+        MegavitaminGround    = 343 => Megavitamin   , "SpecialN",
+        MegavitaminAir       = 344 => Megavitamin   , "SpecialAirN",
+        SuperSheetGround     = 345 => SuperSheet    , "SpecialS",
+        SuperSheetAir        = 346 => SuperSheet    , "SpecialAirS",
+        SuperJumpPunchGround = 347 => SuperJumpPunch, "SpecialHi",
+        SuperJumpPunchAir    = 348 => SuperJumpPunch, "SpecialAirHi",
+        TornadoGround        = 349 => Tornado       , "SpecialLw",
+        TornadoAir           = 350 => Tornado       , "SpecialAirLw",
     }
 }
 
@@ -1188,64 +1243,75 @@ special_states! {
 special_states! {
     Pichu, SpecialActionStatePichu, 
     SpecialBroadStatePichu, HighLevelActionPichu
-    { Todo, ParseAll, NoJumpVariants(), },
     {
-        ThunderJoltGround      = 341 => Todo, "Todo",
-        ThunderJoltAir         = 342 => Todo, "Todo",
-        SkullBashGroundStartup = 343 => Todo, "Todo",
-        SkullBashGroundCharge  = 344 => Todo, "Todo",
-        Unknown345             = 345 => Todo, "Todo",
-        SkullBashGroundLanding = 346 => Todo, "Todo",
-        SkullBashGroundTakeoff = 347 => Todo, "Todo",
-        SkullBashAirStartup    = 348 => Todo, "Todo",
-        SkullBashAirCharge     = 349 => Todo, "Todo",
-        SkullBashAir           = 350 => Todo, "Todo",
-        SkullBashAirEnd        = 351 => Todo, "Todo",
-        SkullBashAirTakeoff    = 352 => Todo, "Todo",
-        AgilityGroundStartup   = 353 => Todo, "Todo",
-        AgilityGround          = 354 => Todo, "Todo",
-        AgilityGroundEnd       = 355 => Todo, "Todo",
-        AgilityAirStartup      = 356 => Todo, "Todo",
-        AgilityAir             = 357 => Todo, "Todo",
-        AgilityAirEnd          = 358 => Todo, "Todo",
-        ThunderGroundStartup   = 359 => Todo, "Todo",
-        ThunderGround          = 360 => Todo, "Todo",
-        ThunderGroundHit       = 361 => Todo, "Todo",
-        ThunderGroundEnd       = 362 => Todo, "Todo",
-        ThunderAirStartup      = 363 => Todo, "Todo",
-        ThunderAir             = 364 => Todo, "Todo",
-        ThunderAirHit          = 365 => Todo, "Todo",
-        ThunderAirEnd          = 366 => Todo, "Todo",
+        ThunderJolt, ParseAll, NoJumpVariants(),
+        SkullBash, ParseAll, NoJumpVariants(),
+        Agility, ParseAll, NoJumpVariants(),
+        Thunder, ParseAll, NoJumpVariants(),
+    },
+    {
+        ThunderJoltGround      = 341 => ThunderJolt, "SpecialN",
+        ThunderJoltAir         = 342 => ThunderJolt, "SpecialAirN",
+        SkullBashGroundStartup = 343 => SkullBash  , "SpecialSStart",
+        SkullBashGroundCharge  = 344 => SkullBash  , "SpecialSHold",
+        Unknown345             = 345 => SkullBash  , "SpecialSHold",
+        SkullBashGroundLanding = 346 => SkullBash  , "SpecialSEnd",
+        SkullBashGroundTakeoff = 347 => SkullBash  , "SpecialS",
+        SkullBashAirStartup    = 348 => SkullBash  , "SpecialAirSStart",
+        SkullBashAirCharge     = 349 => SkullBash  , "SpecialAirSHold",
+        SkullBashAir           = 350 => SkullBash  , "SpecialAirS",
+        SkullBashAirEnd        = 351 => SkullBash  , "SpecialAirSEnd",
+        SkullBashAirTakeoff    = 352 => SkullBash  , "SpecialAirS",
+        AgilityGroundStartup   = 353 => Agility    , "SpecialHiStart",
+        AgilityGround          = 354 => Agility    , "SpecialHi",
+        AgilityGroundEnd       = 355 => Agility    , "SpecialHiEnd",
+        AgilityAirStartup      = 356 => Agility    , "SpecialAirHiStart",
+        AgilityAir             = 357 => Agility    , "SpecialAirHi",
+        AgilityAirEnd          = 358 => Agility    , "SpecialAirHiEnd",
+        ThunderGroundStartup   = 359 => Thunder    , "SpecialLwStart",
+        ThunderGround          = 360 => Thunder    , "SpecialLwLoop",
+        ThunderGroundHit       = 361 => Thunder    , "SpecialLwLoop2",
+        ThunderGroundEnd       = 362 => Thunder    , "SpecialLwEnd",
+        ThunderAirStartup      = 363 => Thunder    , "SpecialAirLwStart",
+        ThunderAir             = 364 => Thunder    , "SpecialAirLwLoop",
+        ThunderAirHit          = 365 => Thunder    , "SpecialAirLwLoop2",
+        ThunderAirEnd          = 366 => Thunder    , "SpecialAirLwEnd",
     }
 }
 
 special_states! {
     Ganondorf, SpecialActionStateGanondorf, 
     SpecialBroadStateGanondorf, HighLevelActionGanondorf
-    { Todo, ParseAll, NoJumpVariants(), },
     {
-        Unknown341                      = 341 => Todo, "Todo",
-        Unknown342                      = 342 => Todo, "Todo",
-        Unknown343                      = 343 => Todo, "Todo",
-        Unknown344                      = 344 => Todo, "Todo",
-        Unknown345                      = 345 => Todo, "Todo",
-        Unknown346                      = 346 => Todo, "Todo",
-        WarlockPunchGround              = 347 => Todo, "Todo",
-        WarlockPunchAir                 = 348 => Todo, "Todo",
-        GerudoDragonGround              = 349 => Todo, "Todo",
-        GerudoDragonGroundHit           = 350 => Todo, "Todo",
-        GerudoDragonAir                 = 351 => Todo, "Todo",
-        GerudoDragonAirHit              = 352 => Todo, "Todo",
-        DarkDiveGround                  = 353 => Todo, "Todo",
-        DarkDiveAir                     = 354 => Todo, "Todo",
-        DarkDiveCatch                   = 355 => Todo, "Todo",
-        DarkDiveEnding                  = 356 => Todo, "Todo",
-        WizardsFootGround               = 357 => Todo, "Todo",
-        WizardsFootGroundEndingOnGround = 358 => Todo, "Todo",
-        WizardsFootAir                  = 359 => Todo, "Todo",
-        WizardsFootAirEndingOnGround    = 360 => Todo, "Todo",
-        WizardsFootAirEndingInAir       = 361 => Todo, "Todo",
-        WizardsFootGroundEndingInAir    = 362 => Todo, "Todo",
-        WizardsFootHitWall              = 363 => Todo, "Todo",
+        Unknown, ParseAll, NoJumpVariants(),
+        WarlockPunch, ParseAll, NoJumpVariants(),
+        GerudoDragon, ParseAll, NoJumpVariants(),
+        DarkDive, ParseAll, NoJumpVariants(),
+        WizardsFoot, ParseAll, NoJumpVariants(),
+    },
+    {
+        Unknown341                      = 341 => Unknown     , "Unknown",
+        Unknown342                      = 342 => Unknown     , "Unknown",
+        Unknown343                      = 343 => Unknown     , "Unknown",
+        Unknown344                      = 344 => Unknown     , "Unknown",
+        Unknown345                      = 345 => Unknown     , "Unknown",
+        Unknown346                      = 346 => Unknown     , "Unknown",
+        WarlockPunchGround              = 347 => WarlockPunch, "SpecialN",
+        WarlockPunchAir                 = 348 => WarlockPunch, "SpecialAirN",
+        GerudoDragonGround              = 349 => GerudoDragon, "SpecialSStart",
+        GerudoDragonGroundHit           = 350 => GerudoDragon, "SpecialS",
+        GerudoDragonAir                 = 351 => GerudoDragon, "SpecialAirSStart",
+        GerudoDragonAirHit              = 352 => GerudoDragon, "SpecialAirS",
+        DarkDiveGround                  = 353 => DarkDive    , "SpecialHi",
+        DarkDiveAir                     = 354 => DarkDive    , "SpecialAirHi",
+        DarkDiveCatch                   = 355 => DarkDive    , "SpecialHiCatch",
+        DarkDiveEnding                  = 356 => DarkDive    , "SpecialHiThrow",
+        WizardsFootGround               = 357 => WizardsFoot , "SpecialLw",
+        WizardsFootGroundEndingOnGround = 358 => WizardsFoot , "SpecialLwEnd",
+        WizardsFootAir                  = 359 => WizardsFoot , "SpecialAirLw",
+        WizardsFootAirEndingOnGround    = 360 => WizardsFoot , "SpecialAirLwEnd",
+        WizardsFootAirEndingInAir       = 361 => WizardsFoot , "SpecialAirLwEndAir",
+        WizardsFootGroundEndingInAir    = 362 => WizardsFoot , "SpecialLwEnd",
+        WizardsFootHitWall              = 363 => WizardsFoot , "SpecialLwEnd",
     }
 }
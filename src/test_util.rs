@@ -0,0 +1,219 @@
+//! Synthetic frames and replay files for unit tests.
+
+use crate::*;
+
+/// A grounded Fox at the origin facing right, on the first animation frame of `state`.
+pub fn frame(state: impl Into<ActionState>) -> Frame {
+    let state = state.into();
+    Frame {
+        character: Character::Fox,
+        port_idx: 0,
+        direction: Direction::Right,
+        velocity: Vector::ZERO,
+        hit_velocity: Vector::ZERO,
+        ground_x_velocity: 0.0,
+        position: Vector::ZERO,
+        state,
+        state_num: state.as_u16(),
+        anim_frame: 1.0,
+        shield_size: 60.0,
+        buttons_mask: 0,
+        analog_trigger_value: 0.0,
+        left_stick_coords: [0.0; 2],
+        right_stick_coords: [0.0; 2],
+        raw_analog_x: 0,
+        rng_seed: 0,
+        percent: 0.0,
+        stock_count: 4,
+        is_airborne: false,
+        hitlag_frames: 0.0,
+        l_cancel: None,
+        hurtbox_state: HurtboxState::Vulnerable,
+        nana: None,
+    }
+}
+
+/// `len` frames of `state`, with the animation frame counting up from one.
+pub fn run(state: impl Into<ActionState>, len: usize) -> Vec<Frame> {
+    let state = state.into();
+    (0..len).map(|i| Frame { anim_frame: i as f32 + 1.0, ..frame(state) }).collect()
+}
+
+/// Runs of each state in turn.
+pub fn frames(runs: &[(StandardActionState, usize)]) -> Vec<Frame> {
+    runs.iter().flat_map(|&(state, len)| run(state, len)).collect()
+}
+
+/// An action with every optional field unset, actionable from its first frame.
+pub fn action(action_taken: HighLevelAction, frame_start: usize, frame_end: usize) -> Action {
+    Action {
+        start_state: BroadState::Standard(StandardBroadState::Ground),
+        action_taken,
+        frame_start,
+        frame_end,
+        initial_position: Vector::ZERO,
+        initial_velocity: Vector::ZERO,
+        l_cancelled: None,
+        b_reversed: false,
+        edge_cancelled: false,
+        wavedash_angle: None,
+        galint: None,
+        pummels: 0,
+        di_angle: None,
+        sdi_inputs: 0,
+        jab_hits: 0,
+        waveland_surface: None,
+        actionable_frame: frame_start,
+        from_shield: false,
+        oos_frames: None,
+        shield_frames: None,
+        grab_frames: None,
+        inactionable_state: None,
+        frames_since_prev: 0,
+    }
+}
+
+const EVENT_PAYLOADS:       u8 = 0x35;
+const GAME_START:           u8 = 0x36;
+const PRE_FRAME_UPDATE:     u8 = 0x37;
+const POST_FRAME_UPDATE:    u8 = 0x38;
+const GAME_END:             u8 = 0x39;
+const FRAME_START:          u8 = 0x3A;
+const FRAME_BOOKEND:        u8 = 0x3C;
+
+const PAYLOAD_SIZES: [(u8, u16); 6] = [
+    (GAME_START, 0x2FF),
+    (PRE_FRAME_UPDATE, 0x40),
+    (POST_FRAME_UPDATE, 0x50),
+    (GAME_END, 0x06),
+    (FRAME_START, 0x08),
+    (FRAME_BOOKEND, 0x08),
+];
+
+/// Writes an uncompressed replay of Fox against Fox on Battlefield, in ports one and two unless moved with `ports`.
+pub struct ReplayBuilder {
+    raw: Vec<u8>,
+    ports: [u8; 2],
+    last_frame: Option<i32>,
+}
+
+impl ReplayBuilder {
+    pub fn new() -> Self {
+        let mut raw = vec![EVENT_PAYLOADS, 1 + 3 * PAYLOAD_SIZES.len() as u8];
+        for (code, size) in PAYLOAD_SIZES {
+            raw.push(code);
+            raw.extend_from_slice(&size.to_be_bytes());
+        }
+
+        let mut game_start = vec![0u8; 0x2FF];
+        game_start[0..3].copy_from_slice(&[3, 18, 0]);
+        game_start[0x0F] = 0xFF; // items off
+        game_start[0x12..0x14].copy_from_slice(&31u16.to_be_bytes()); // battlefield
+        for i in 0..4 {
+            game_start[0x64 + 0x24 * i] = 2; // fox
+            game_start[0x65 + 0x24 * i] = if i < 2 { 0 } else { 3 };
+            game_start[0x66 + 0x24 * i] = 4;
+        }
+        raw.push(GAME_START);
+        raw.extend_from_slice(&game_start);
+
+        ReplayBuilder { raw, ports: [0, 1], last_frame: None }
+    }
+
+    /// Moves the players to other zero indexed controller ports. Must be called before writing frames.
+    pub fn ports(mut self, ports: [u8; 2]) -> Self {
+        let game_start = self.raw.len() - 0x2FF;
+        for i in 0..4 {
+            let human = ports.contains(&(i as u8));
+            self.raw[game_start + 0x65 + 0x24 * i] = if human { 0 } else { 3 };
+        }
+        self.ports = ports;
+        self
+    }
+
+    /// Writes a finished frame for both players.
+    pub fn frame(self, frame_num: i32, frames: [&Frame; 2]) -> Self {
+        self.frame_finalized(frame_num, frame_num, frames)
+    }
+
+    /// Like `frame`, but with the latest finalized frame set in the bookend.
+    /// Writing a frame number that was already written is a rollback.
+    pub fn frame_finalized(mut self, frame_num: i32, finalized: i32, frames: [&Frame; 2]) -> Self {
+        self = self.unfinished_frame(frame_num, frames);
+
+        let mut bookend = [0u8; 0x08];
+        bookend[0..4].copy_from_slice(&frame_num.to_be_bytes());
+        bookend[4..8].copy_from_slice(&finalized.to_be_bytes());
+        self.event(FRAME_BOOKEND, &bookend);
+        self
+    }
+
+    /// Writes a frame with no bookend, as when a rollback interrupts the frame.
+    pub fn unfinished_frame(mut self, frame_num: i32, frames: [&Frame; 2]) -> Self {
+        let mut frame_start = [0u8; 0x08];
+        frame_start[0..4].copy_from_slice(&frame_num.to_be_bytes());
+        self.event(FRAME_START, &frame_start);
+
+        for (port, f) in self.ports.into_iter().zip(frames) {
+            let mut pre = [0u8; 0x40];
+            pre[0..4].copy_from_slice(&frame_num.to_be_bytes());
+            pre[4] = port;
+            pre[0x18..0x1C].copy_from_slice(&f.left_stick_coords[0].to_be_bytes());
+            pre[0x1C..0x20].copy_from_slice(&f.left_stick_coords[1].to_be_bytes());
+            pre[0x28..0x2C].copy_from_slice(&f.analog_trigger_value.to_be_bytes());
+            pre[0x30..0x32].copy_from_slice(&f.buttons_mask.to_be_bytes());
+            self.event(PRE_FRAME_UPDATE, &pre);
+        }
+
+        for (port, f) in self.ports.into_iter().zip(frames) {
+            let mut post = [0u8; 0x50];
+            post[0..4].copy_from_slice(&frame_num.to_be_bytes());
+            post[4] = port;
+            post[6] = 1; // fox
+            post[0x7..0x9].copy_from_slice(&f.state_num.to_be_bytes());
+            post[0x9..0xD].copy_from_slice(&f.position.x.to_be_bytes());
+            post[0xD..0x11].copy_from_slice(&f.position.y.to_be_bytes());
+            post[0x11..0x15].copy_from_slice(&f.direction.sign().to_be_bytes());
+            post[0x15..0x19].copy_from_slice(&f.percent.to_be_bytes());
+            post[0x19..0x1D].copy_from_slice(&f.shield_size.to_be_bytes());
+            post[0x20] = f.stock_count;
+            post[0x21..0x25].copy_from_slice(&f.anim_frame.to_be_bytes());
+            post[0x2E] = f.is_airborne as u8;
+            post[0x34..0x38].copy_from_slice(&f.velocity.x.to_be_bytes());
+            post[0x38..0x3C].copy_from_slice(&f.velocity.y.to_be_bytes());
+            post[0x48..0x4C].copy_from_slice(&f.hitlag_frames.to_be_bytes());
+            self.event(POST_FRAME_UPDATE, &post);
+        }
+        self
+    }
+
+    /// Ends the game by stocks, with port one winning.
+    pub fn game_end(mut self) -> Self {
+        self.event(GAME_END, &[2, 0xFF, 0, 1, 0xFF, 0xFF]);
+        self
+    }
+
+    /// Writes `lastFrame` to the metadata. Crashed games have no last frame.
+    pub fn last_frame(mut self, last_frame: i32) -> Self {
+        self.last_frame = Some(last_frame);
+        self
+    }
+
+    pub fn build(self) -> Vec<u8> {
+        let mut bytes = b"{U\x03raw[$U#l".to_vec();
+        bytes.extend_from_slice(&(self.raw.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(&self.raw);
+        bytes.extend_from_slice(b"U\x08metadata{");
+        if let Some(last_frame) = self.last_frame {
+            bytes.extend_from_slice(b"U\x09lastFramel");
+            bytes.extend_from_slice(&last_frame.to_be_bytes());
+        }
+        bytes.extend_from_slice(b"}}");
+        bytes
+    }
+
+    fn event(&mut self, code: u8, payload: &[u8]) {
+        self.raw.push(code);
+        self.raw.extend_from_slice(payload);
+    }
+}